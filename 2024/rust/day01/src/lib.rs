@@ -1,2 +1,6 @@
+pub mod parsers;
 pub mod part1;
 pub mod part2;
+
+#[cfg(test)]
+generators::fuzz_tests!(1);
@@ -0,0 +1,22 @@
+use miette::{miette, Context, IntoDiagnostic};
+
+// The two columns of location ids, one pair to a line
+pub fn parse(input: &str) -> miette::Result<(Vec<i64>, Vec<i64>)> {
+  let mut left = vec![];
+  let mut right = vec![];
+
+  for (number, line) in input.lines().enumerate() {
+    let mut items = line.split_ascii_whitespace();
+    let (Some(l), Some(r), None) = (items.next(), items.next(), items.next()) else {
+      return Err(miette!("line {}: expected two location ids", number + 1));
+    };
+    for (item, column) in [(l, &mut left), (r, &mut right)] {
+      let id: i32 = item
+        .parse()
+        .into_diagnostic()
+        .wrap_err_with(|| format!("line {}: location id {item:?}", number + 1))?;
+      column.push(id.into());
+    }
+  }
+  Ok((left, right))
+}
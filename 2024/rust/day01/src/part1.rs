@@ -1,17 +1,12 @@
-pub fn process(input: &str) -> miette::Result<String> {
-  let mut left = vec![];
-  let mut right = vec![];
+use crate::parsers::parse;
 
-  for line in input.lines() {
-    let mut items = line.split_ascii_whitespace();
-    left.push(items.next().unwrap().parse::<i32>().unwrap());
-    right.push(items.next().unwrap().parse::<i32>().unwrap());
-  }
+pub fn process(input: &str) -> miette::Result<String> {
+  let (mut left, mut right) = parse(input)?;
 
   left.sort();
   right.sort();
 
-  let result: i32 = std::iter::zip(left, right)
+  let result: i64 = std::iter::zip(left, right)
     .map(|(l, r)| (l - r).abs())
     .sum();
 
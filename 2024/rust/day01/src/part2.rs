@@ -1,18 +1,14 @@
-pub fn process(input: &str) -> miette::Result<String> {
-  let mut left = vec![];
-  let mut right = vec![];
+use crate::parsers::parse;
+use miette::miette;
 
-  for line in input.lines() {
-    let mut items = line.split_ascii_whitespace();
-    left.push(items.next().unwrap().parse::<usize>().unwrap());
-    right.push(items.next().unwrap().parse::<usize>().unwrap());
-  }
+pub fn process(input: &str) -> miette::Result<String> {
+  let (left, right) = parse(input)?;
 
-  let result: usize = left
+  let result = left
     .iter()
-    .map(|l| l * right.iter().filter(|r| &l == r).count())
-    .sum();
-
+    .map(|l| l * right.iter().filter(|r| &l == r).count() as i64)
+    .try_fold(0i64, |total, score| total.checked_add(score))
+    .ok_or_else(|| miette!("similarity score is too big"))?;
   Ok(result.to_string())
 }
 
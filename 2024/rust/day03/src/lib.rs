@@ -1,2 +1,5 @@
 pub mod part1;
 pub mod part2;
+
+#[cfg(test)]
+generators::fuzz_tests!(3);
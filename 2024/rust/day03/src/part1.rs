@@ -2,7 +2,7 @@ use miette;
 use nom::{
   bytes::complete::tag,
   character::complete::{self, anychar},
-  multi::{many0, many1, many_till},
+  multi::{many1, many_till},
   sequence::{delimited, separated_pair},
   IResult, Parser,
 };
@@ -17,12 +17,13 @@ pub fn process(input: &str) -> miette::Result<String> {
   // Nom IResult is not compatible with miette::Result, so have to convert the nom error using .map_err()
   let (_leftover_input, instructions) =
     parse(input).map_err(|e| miette::miette!("parse failed {}", e))?;
-  let sum: u32 = instructions
+  // A u32 times a u32 always fits in a u64, the total might not
+  let sum = instructions
     .iter()
-    .map(|instruction| match instruction {
-      Instruction::Mul(a, b) => a * b,
+    .try_fold(0u64, |sum, instruction| match instruction {
+      Instruction::Mul(a, b) => sum.checked_add(u64::from(*a) * u64::from(*b)),
     })
-    .sum();
+    .ok_or_else(|| miette::miette!("total doesn't fit in a u64"))?;
   Ok(sum.to_string())
 }
 
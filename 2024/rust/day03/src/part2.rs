@@ -25,14 +25,21 @@ enum Processing {
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
   let (_input, instructions) = parse(input).map_err(|e| miette::miette!("parse failed {}", e))?;
+  // A u32 times a u32 always fits in a u64, the total might not
   let (_, result) = instructions
     .iter()
-    .fold((Processing::Enabled, 0), |(process, total), op| match op {
-      Instruction::Mul(a, b) if process == Processing::Enabled => (process, total + a * b),
-      Instruction::Mul(_, _) => (process, total),
-      Instruction::Do => (Processing::Enabled, total),
-      Instruction::Dont => (Processing::Disabled, total),
-    });
+    .try_fold(
+      (Processing::Enabled, 0u64),
+      |(process, total), op| match op {
+        Instruction::Mul(a, b) if process == Processing::Enabled => {
+          Some((process, total.checked_add(u64::from(*a) * u64::from(*b))?))
+        }
+        Instruction::Mul(_, _) => Some((process, total)),
+        Instruction::Do => Some((Processing::Enabled, total)),
+        Instruction::Dont => Some((Processing::Disabled, total)),
+      },
+    )
+    .ok_or_else(|| miette::miette!("total doesn't fit in a u64"))?;
   Ok(result.to_string())
 }
 
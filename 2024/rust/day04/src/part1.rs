@@ -4,11 +4,20 @@ struct Grid {
   cols: usize,
 }
 
-impl From<&str> for Grid {
-  fn from(input: &str) -> Self {
+impl TryFrom<&str> for Grid {
+  type Error = miette::Report;
+
+  fn try_from(input: &str) -> miette::Result<Self> {
     let bytes: Vec<Vec<u8>> = input.lines().map(|row| row.bytes().collect()).collect();
     let (rows, cols) = (bytes.len(), bytes.first().map_or(0, |row| row.len()));
-    Self { bytes, rows, cols }
+    if let Some(row) = bytes.iter().position(|row| row.len() != cols) {
+      return Err(miette::miette!(
+        "row {} is {} letters long, the first is {cols}",
+        row + 1,
+        bytes[row].len()
+      ));
+    }
+    Ok(Self { bytes, rows, cols })
   }
 }
 
@@ -46,7 +55,7 @@ impl Grid {
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-  let grid = Grid::from(input);
+  let grid = Grid::try_from(input)?;
   let mut counter = 0;
   (0..grid.rows)
     .flat_map(|row| (0..grid.cols).map(move |col| (row, col)))
@@ -1,3 +1,6 @@
+use miette::miette;
+use std::collections::HashSet;
+
 use nom::{
   self,
   bytes::complete::tag,
@@ -12,19 +15,23 @@ type Updates = Vec<Vec<u32>>;
 
 #[derive(Debug)]
 struct Manual {
-  rules: Rules,
+  rules: HashSet<(u32, u32)>,
   updates: Updates,
 }
 
-impl From<&str> for Manual {
-  fn from(input: &str) -> Self {
-    let Ok((_input, (parsed_rules, parsed_updates))) = parse(input) else {
-      panic!("An error occurred while parsing input and creating the Manual")
-    };
-    Self {
-      rules: parsed_rules,
-      updates: parsed_updates,
+impl TryFrom<&str> for Manual {
+  type Error = miette::Report;
+
+  fn try_from(input: &str) -> miette::Result<Self> {
+    let (rest, (parsed_rules, parsed_updates)) =
+      parse(input).map_err(|e| miette!("Error parsing the manual: {e}"))?;
+    if !rest.trim().is_empty() {
+      return Err(miette!("Unexpected text after the updates: {rest:?}"));
     }
+    Ok(Self {
+      rules: parsed_rules.into_iter().collect(),
+      updates: parsed_updates,
+    })
   }
 }
 
@@ -83,15 +90,15 @@ fn updates_parser(input: &str) -> IResult<&str, Updates> {
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-  let manual = Manual::from(input);
-  let mut sum: u32 = 0;
+  let manual = Manual::try_from(input)?;
+  let mut sum: u64 = 0;
   manual
     .updates
     .iter()
     .filter(|&update| manual.valid_update(update))
     .for_each(|update| {
       let mid = update.len() / 2;
-      sum += update[mid]
+      sum += u64::from(update[mid])
     });
   Ok(sum.to_string())
 }
@@ -1,4 +1,5 @@
-use std::cmp::Ordering;
+use miette::miette;
+use std::collections::HashSet;
 
 use nom::{
   self,
@@ -14,19 +15,23 @@ type Updates = Vec<Vec<u32>>;
 
 #[derive(Debug)]
 struct Manual {
-  rules: Rules,
+  rules: HashSet<(u32, u32)>,
   updates: Updates,
 }
 
-impl From<&str> for Manual {
-  fn from(input: &str) -> Self {
-    let Ok((_input, (parsed_rules, parsed_updates))) = parse(input) else {
-      panic!("An error occurred while parsing input and creating the Manual")
-    };
-    Self {
-      rules: parsed_rules,
-      updates: parsed_updates,
+impl TryFrom<&str> for Manual {
+  type Error = miette::Report;
+
+  fn try_from(input: &str) -> miette::Result<Self> {
+    let (rest, (parsed_rules, parsed_updates)) =
+      parse(input).map_err(|e| miette!("Error parsing the manual: {e}"))?;
+    if !rest.trim().is_empty() {
+      return Err(miette!("Unexpected text after the updates: {rest:?}"));
     }
+    Ok(Self {
+      rules: parsed_rules.into_iter().collect(),
+      updates: parsed_updates,
+    })
   }
 }
 
@@ -42,17 +47,19 @@ impl Manual {
     })
   }
 
-  fn fix_order(&self, mut update: Vec<u32>) -> Vec<u32> {
-    update.sort_by(|&first, &second| {
-      if self.rules.contains(&(first, second)) {
-        Ordering::Less
-      } else if self.rules.contains(&(second, first)) {
-        Ordering::Greater
-      } else {
-        Ordering::Equal
-      }
-    });
-    update
+  // Repeatedly take the page that no other page left has to come before.
+  // None if the rules for these pages go round in a circle
+  fn fix_order(&self, mut update: Vec<u32>) -> Option<Vec<u32>> {
+    let mut ordered = Vec::with_capacity(update.len());
+    while !update.is_empty() {
+      let next = update.iter().position(|&page| {
+        !update
+          .iter()
+          .any(|&other| self.rules.contains(&(other, page)))
+      })?;
+      ordered.push(update.remove(next));
+    }
+    Some(ordered)
   }
 }
 
@@ -79,17 +86,19 @@ fn updates_parser(input: &str) -> IResult<&str, Updates> {
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-  let manual = Manual::from(input);
-  let mut sum: u32 = 0;
-  manual
+  let manual = Manual::try_from(input)?;
+  let mut sum: u64 = 0;
+  for update in manual
     .updates
     .iter()
     .filter(|&update| !manual.valid_update(update))
-    .map(|update| manual.fix_order(update.clone()))
-    .for_each(|update| {
-      let mid = update.len() / 2;
-      sum += update[mid]
-    });
+  {
+    let update = manual
+      .fix_order(update.clone())
+      .ok_or_else(|| miette!("The rules for update {update:?} contradict each other"))?;
+    let mid = update.len() / 2;
+    sum += u64::from(update[mid]);
+  }
   Ok(sum.to_string())
 }
 
@@ -130,4 +139,9 @@ mod tests {
     assert_eq!("123", process(input)?);
     Ok(())
   }
+
+  #[test]
+  fn test_rules_in_a_circle() {
+    assert!(process("30|29\n29|65\n65|30\n\n65,29,30\n").is_err());
+  }
 }
@@ -2,8 +2,11 @@ mod maze;
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-  let mut labyrinth = maze::Labyrinth::from(input);
-  let result = labyrinth.walk().len();
+  let mut labyrinth = maze::Labyrinth::try_from(input)?;
+  let result = labyrinth
+    .walk()
+    .ok_or_else(|| miette::miette!("The guard never leaves the map"))?
+    .len();
   Ok(result.to_string())
 }
 
@@ -2,8 +2,10 @@ mod maze;
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-  let mut labyrinth = maze::Labyrinth::from(input);
-  let maze_path = labyrinth.walk();
+  let mut labyrinth = maze::Labyrinth::try_from(input)?;
+  let maze_path = labyrinth
+    .walk()
+    .ok_or_else(|| miette::miette!("The guard never leaves the map"))?;
 
   let count = maze_path
     .iter()
@@ -15,6 +17,8 @@ pub fn process(input: &str) -> miette::Result<String> {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use maze::Point;
+  use proptest::{prelude::*, test_runner::RngSeed};
 
   #[test]
   fn test_process() -> miette::Result<()> {
@@ -31,4 +35,60 @@ mod tests {
     assert_eq!("6", process(input)?);
     Ok(())
   }
+
+  // Maps of up to 8 by 8 with some obstructions and the guard somewhere
+  fn guard_map() -> impl Strategy<Value = String> {
+    (1..9usize, 1..9usize)
+      .prop_flat_map(|(rows, cols)| {
+        (
+          prop::collection::vec(prop::bool::weighted(0.2), rows * cols),
+          0..rows * cols,
+          Just(cols),
+        )
+      })
+      .prop_map(|(walls, guard, cols)| {
+        let cells: Vec<char> = walls
+          .iter()
+          .enumerate()
+          .map(|(cell, &wall)| match (cell == guard, wall) {
+            (true, _) => '^',
+            (false, true) => '#',
+            (false, false) => '.',
+          })
+          .collect();
+        cells
+          .chunks(cols)
+          .map(|row| row.iter().collect::<String>())
+          .collect::<Vec<_>>()
+          .join("\n")
+      })
+  }
+
+  proptest! {
+    #![proptest_config(ProptestConfig {
+      rng_seed: RngSeed::Fixed(6),
+      ..ProptestConfig::default()
+    })]
+
+    // An obstruction the guard never walks into can't change where they go,
+    // so only tiles on the original path need trying
+    #[test]
+    fn test_loops_only_from_the_path(input in guard_map()) {
+      let mut labyrinth = maze::Labyrinth::try_from(input.as_str()).unwrap();
+      let path = labyrinth.walk();
+      // Only maps the guard gets out of have an answer
+      prop_assume!(path.is_some());
+      let path = path.unwrap();
+      prop_assert_eq!(crate::part1::process(&input).unwrap(), path.len().to_string());
+
+      for (row, line) in input.lines().enumerate() {
+        for (col, cell) in line.chars().enumerate() {
+          let obstacle = Point(row as i32, col as i32);
+          if cell == '.' && !path.contains(&obstacle) {
+            prop_assert!(!labyrinth.looping(obstacle), "{:?}", obstacle);
+          }
+        }
+      }
+    }
+  }
 }
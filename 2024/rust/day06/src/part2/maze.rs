@@ -9,8 +9,10 @@ pub struct Labyrinth {
   pub origin: Guard,
 }
 
-impl From<&str> for Labyrinth {
-  fn from(input: &str) -> Self {
+impl TryFrom<&str> for Labyrinth {
+  type Error = miette::Report;
+
+  fn try_from(input: &str) -> miette::Result<Self> {
     let parsed_grid: Grid = input.lines().map(|line| line.bytes().collect()).collect();
     let home = parsed_grid
       .iter()
@@ -21,8 +23,8 @@ impl From<&str> for Labyrinth {
           .position(|&b| b == b'^')
           .map(|y| Point(x as i32, y as i32))
       })
-      .unwrap_or_default();
-    Self {
+      .ok_or_else(|| miette::miette!("No guard on the map"))?;
+    Ok(Self {
       grid: parsed_grid,
       guard: Guard {
         position: home,
@@ -32,14 +34,20 @@ impl From<&str> for Labyrinth {
         position: home,
         direction: Direction::Up,
       },
-    }
+    })
   }
 }
 
 impl Labyrinth {
-  pub fn walk(&mut self) -> HashSet<Point> {
+  // Every position the guard stands on before walking off the map, or None
+  // if they go round in a loop and never do
+  pub fn walk(&mut self) -> Option<HashSet<Point>> {
     let mut path: HashSet<Point> = HashSet::new();
+    let mut seen = HashSet::new();
     loop {
+      if !seen.insert((self.guard.position, self.guard.direction)) {
+        return None;
+      }
       path.insert(self.guard.position);
       let next = self.guard.position + self.guard.direction.offset();
       match self.get(next) {
@@ -48,7 +56,7 @@ impl Labyrinth {
         None => break,
       }
     }
-    path
+    Some(path)
   }
 
   fn get(&self, Point(x, y): Point) -> Option<u8> {
@@ -105,7 +113,7 @@ pub struct Guard {
 }
 
 #[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
-pub struct Point(i32, i32);
+pub struct Point(pub i32, pub i32);
 
 impl Add<Offset> for Point {
   type Output = Self;
@@ -1,19 +1,20 @@
+use miette::miette;
+
 type Equations = Vec<(u64, Vec<u64>)>;
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-  let calibration_table: Equations = parse_input(input);
+  let calibration_table: Equations = parse_input(input)?;
 
-  Ok(
-    calibration_table
-      .iter()
-      .filter(|(total, factors)| solve(total, factors))
-      .map(|(test, _)| test)
-      .sum::<u64>()
-      .to_string(),
-  )
+  calibration_table
+    .iter()
+    .filter(|(total, factors)| solve(total, factors))
+    .try_fold(0u64, |sum, (test, _)| sum.checked_add(*test))
+    .map(|sum| sum.to_string())
+    .ok_or_else(|| miette!("The calibration total doesn't fit in a u64"))
 }
 
+// Anything that overflows a u64 is dropped along the way
 fn solve(total: &u64, factors: &[u64]) -> bool {
   factors
     .iter()
@@ -21,25 +22,37 @@ fn solve(total: &u64, factors: &[u64]) -> bool {
     .fold(vec![factors[0]], |acc, &next_num| {
       acc
         .iter()
-        .flat_map(|&previous_num| vec![previous_num + next_num, previous_num * next_num])
+        .flat_map(|&previous_num| {
+          [
+            previous_num.checked_add(next_num),
+            previous_num.checked_mul(next_num),
+          ]
+        })
+        .flatten()
         .collect()
     })
     .contains(total)
 }
 
-fn parse_input(input: &str) -> Equations {
+// One "total: factor factor ..." per line, with at least one factor
+fn parse_input(input: &str) -> miette::Result<Equations> {
   input
     .lines()
-    .filter_map(|line| {
-      let (total, rest) = line.split_once(":")?;
-      let total = total.trim().parse().ok()?;
-      let factors = rest
+    .filter(|line| !line.trim().is_empty())
+    .map(|line| {
+      let bad_line = || miette!("Bad equation {line:?}");
+      let (total, rest) = line.split_once(":").ok_or_else(bad_line)?;
+      let total = total.trim().parse().map_err(|_| bad_line())?;
+      let factors: Vec<u64> = rest
         .split_whitespace()
-        .filter_map(|i| i.parse().ok())
-        .collect();
-      Some((total, factors))
+        .map(|i| i.parse().map_err(|_| bad_line()))
+        .collect::<miette::Result<_>>()?;
+      if factors.is_empty() {
+        return Err(bad_line());
+      }
+      Ok((total, factors))
     })
-    .collect::<Equations>()
+    .collect()
 }
 
 #[cfg(test)]
@@ -1,37 +1,39 @@
+use miette::miette;
+
 type Equations = Vec<(u64, Vec<u64>)>;
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-  let calibration_table = parse_input(input);
+  let calibration_table = parse_input(input)?;
 
-  Ok(
-    calibration_table
-      .iter()
-      .filter(|(answer, factors)| solve(answer, factors))
-      .map(|(answer, _)| answer) // destructure the answer from the tuple
-      .sum::<u64>() // and then sum all the answers
-      .to_string(),
-  )
+  calibration_table
+    .iter()
+    .filter(|(answer, factors)| solve(answer, factors))
+    .try_fold(0u64, |sum, (answer, _)| sum.checked_add(*answer)) // sum all the answers
+    .map(|sum| sum.to_string())
+    .ok_or_else(|| miette!("The calibration total doesn't fit in a u64"))
 }
 
 fn solve(total: &u64, factors: &[u64]) -> bool {
   // a || b = a * 10 ^ (1 + floor(log10(b))) + b
-  let concat_num = |a, b| {
+  // Anything that overflows a u64 is dropped along the way
+  let concat_num = |a: u64, b: u64| {
     let power = (b as f64).log10().floor() as u32 + 1;
-    a * 10u64.pow(power) + b
+    a.checked_mul(10u64.checked_pow(power)?)?.checked_add(b)
   };
 
   // Perform add, multiply, concat ops on each pair of numbers and their results
-  let tally = |acc: Vec<_>, next_num: &u64| {
+  let tally = |acc: Vec<u64>, next_num: &u64| {
     acc
       .iter()
       .flat_map(|&previous_num| {
-        vec![
-          previous_num + next_num,
-          previous_num * next_num,
+        [
+          previous_num.checked_add(*next_num),
+          previous_num.checked_mul(*next_num),
           concat_num(previous_num, *next_num),
         ]
       })
+      .flatten()
       .collect()
   };
 
@@ -43,19 +45,25 @@ fn solve(total: &u64, factors: &[u64]) -> bool {
     .contains(total)
 }
 
-fn parse_input(input: &str) -> Equations {
+// One "total: factor factor ..." per line, with at least one factor
+fn parse_input(input: &str) -> miette::Result<Equations> {
   input
     .lines()
-    .filter_map(|line| {
-      let (total, rest) = line.split_once(":")?;
-      let total = total.trim().parse().ok()?;
-      let factors = rest
+    .filter(|line| !line.trim().is_empty())
+    .map(|line| {
+      let bad_line = || miette!("Bad equation {line:?}");
+      let (total, rest) = line.split_once(":").ok_or_else(bad_line)?;
+      let total = total.trim().parse().map_err(|_| bad_line())?;
+      let factors: Vec<u64> = rest
         .split_whitespace()
-        .filter_map(|i| i.parse().ok())
-        .collect();
-      Some((total, factors))
+        .map(|i| i.parse().map_err(|_| bad_line()))
+        .collect::<miette::Result<_>>()?;
+      if factors.is_empty() {
+        return Err(bad_line());
+      }
+      Ok((total, factors))
     })
-    .collect::<Equations>()
+    .collect()
 }
 
 #[cfg(test)]
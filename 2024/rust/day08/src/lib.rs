@@ -1,2 +1,6 @@
 pub mod part1;
 pub mod part2;
+pub mod rooftops;
+
+#[cfg(test)]
+generators::fuzz_tests!(8);
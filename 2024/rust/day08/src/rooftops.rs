@@ -0,0 +1,272 @@
+use std::{
+  collections::{HashMap, HashSet},
+  ops::{Add, AddAssign, Neg, Sub},
+};
+
+use itertools::Itertools;
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Point(pub i32, pub i32);
+
+impl Add for Point {
+  type Output = Self;
+
+  fn add(self, other: Self) -> Self {
+    Self(self.0 + other.0, self.1 + other.1)
+  }
+}
+
+impl Sub for Point {
+  type Output = Self;
+
+  fn sub(self, other: Self) -> Self {
+    Self(self.0 - other.0, self.1 - other.1)
+  }
+}
+
+impl Neg for Point {
+  type Output = Self;
+
+  fn neg(self) -> Self {
+    Self(-self.0, -self.1)
+  }
+}
+
+impl AddAssign for Point {
+  fn add_assign(&mut self, other: Self) {
+    *self = Self(self.0 + other.0, self.1 + other.1)
+  }
+}
+
+fn gcd(a: i32, b: i32) -> i32 {
+  if b == 0 {
+    a.abs()
+  } else {
+    gcd(b, a % b)
+  }
+}
+
+// How a pair of same-frequency antennas projects antinodes onto the rooftops
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AntinodeRule {
+  // Part 1: one antinode beyond each antenna, at twice the distance from the other one
+  Doubled,
+  // Part 2: every grid point in line with the pair, stepping by the full pair offset,
+  // including the antennas themselves
+  Harmonics,
+  // Like Harmonics, but only the first k steps out from each antenna (the antenna
+  // itself is not counted), so Limited(1) is the same as Doubled
+  Limited(usize),
+  // Every lattice point on the line through the pair, stepping by the gcd-reduced
+  // offset, so points between the antennas are hit as well
+  Lattice,
+}
+
+#[derive(Debug)]
+pub struct Rooftops {
+  grid: Vec<Vec<u8>>,
+  antennas: HashMap<u8, Vec<Point>>,
+}
+
+impl From<&str> for Rooftops {
+  fn from(input: &str) -> Self {
+    let grid: Vec<Vec<u8>> = input.lines().map(|line| line.bytes().collect()).collect();
+
+    // Is it bad to nest this much when composing with iters?
+    let antennas = grid
+      .iter()
+      .enumerate()
+      .flat_map(|(x, row)| {
+        row.iter().enumerate().filter_map(move |(y, &antenna)| {
+          (antenna != b'.').then_some((antenna, Point(x as i32, y as i32)))
+        })
+      })
+      .fold(
+        HashMap::new(),
+        |mut possible_antennas, (frequency, position)| {
+          possible_antennas
+            .entry(frequency)
+            // insert default if empty and return mut reference to entry
+            .or_insert_with(Vec::new)
+            .push(position);
+          possible_antennas
+        },
+      );
+
+    Self { grid, antennas }
+  }
+}
+
+impl Rooftops {
+  pub fn get(&self, Point(x, y): Point) -> Option<u8> {
+    self.grid.get(x as usize)?.get(y as usize).copied()
+  }
+
+  pub fn frequencies(&self) -> impl Iterator<Item = u8> + '_ {
+    self.antennas.keys().copied().sorted()
+  }
+
+  // Each unordered pair of same-frequency antennas once - the rules are symmetric,
+  // so (p2, p1) would only repeat the work done for (p1, p2)
+  fn pairs(&self, frequency: u8) -> impl Iterator<Item = (Point, Point)> + '_ {
+    self
+      .antennas
+      .get(&frequency)
+      .into_iter()
+      .flat_map(|antenna| antenna.iter().copied().tuple_combinations())
+  }
+
+  // Walk from start by offset until we fall off the roof, or run out of steps
+  fn project(&self, start: Point, offset: Point, steps: usize, antinodes: &mut HashSet<Point>) {
+    let mut point = start;
+    for _ in 0..steps {
+      if self.get(point).is_none() {
+        break;
+      }
+      antinodes.insert(point);
+      point += offset;
+    }
+  }
+
+  fn pair_antinodes(
+    &self,
+    rule: AntinodeRule,
+    p1: Point,
+    p2: Point,
+    antinodes: &mut HashSet<Point>,
+  ) {
+    let offset = p1 - p2;
+    match rule {
+      AntinodeRule::Doubled => {
+        self.project(p1 + offset, offset, 1, antinodes);
+        self.project(p2 - offset, -offset, 1, antinodes);
+      }
+      AntinodeRule::Harmonics => {
+        self.project(p1, offset, usize::MAX, antinodes);
+        self.project(p2, -offset, usize::MAX, antinodes);
+      }
+      AntinodeRule::Limited(k) => {
+        self.project(p1 + offset, offset, k, antinodes);
+        self.project(p2 - offset, -offset, k, antinodes);
+      }
+      AntinodeRule::Lattice => {
+        let divisor = gcd(offset.0, offset.1);
+        let step = Point(offset.0 / divisor, offset.1 / divisor);
+        self.project(p1, step, usize::MAX, antinodes);
+        self.project(p1, -step, usize::MAX, antinodes);
+      }
+    }
+  }
+
+  pub fn antinodes_for(&self, rule: AntinodeRule, frequency: u8) -> HashSet<Point> {
+    let mut antinodes = HashSet::new();
+    for (p1, p2) in self.pairs(frequency) {
+      self.pair_antinodes(rule, p1, p2, &mut antinodes);
+    }
+    antinodes
+  }
+
+  pub fn antinodes(&self, rule: AntinodeRule) -> HashSet<Point> {
+    self
+      .frequencies()
+      .flat_map(|frequency| self.antinodes_for(rule, frequency))
+      .collect()
+  }
+
+  // Draw the map with a '#' over every antinode that isn't already an antenna,
+  // for a single frequency or for all of them
+  pub fn render(&self, rule: AntinodeRule, frequency: Option<u8>) -> String {
+    let antinodes = match frequency {
+      Some(frequency) => self.antinodes_for(rule, frequency),
+      None => self.antinodes(rule),
+    };
+    self
+      .grid
+      .iter()
+      .enumerate()
+      .map(|(x, row)| {
+        row
+          .iter()
+          .enumerate()
+          .map(|(y, &cell)| {
+            if cell == b'.' && antinodes.contains(&Point(x as i32, y as i32)) {
+              '#'
+            } else {
+              cell as char
+            }
+          })
+          .collect::<String>()
+      })
+      .join("\n")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const INPUT: &str = "............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............";
+
+  #[test]
+  fn test_limited_one_matches_doubled() {
+    let rooftops = Rooftops::from(INPUT);
+    assert_eq!(
+      rooftops.antinodes(AntinodeRule::Doubled),
+      rooftops.antinodes(AntinodeRule::Limited(1))
+    );
+  }
+
+  #[test]
+  fn test_lattice_hits_points_between_antennas() {
+    let rooftops = Rooftops::from(
+      "a....
+.....
+..a..
+.....
+.....",
+    );
+    assert_eq!(3, rooftops.antinodes(AntinodeRule::Harmonics).len());
+    assert_eq!(5, rooftops.antinodes(AntinodeRule::Lattice).len());
+    assert!(rooftops
+      .antinodes(AntinodeRule::Lattice)
+      .contains(&Point(1, 1)));
+  }
+
+  #[test]
+  fn test_render() {
+    let rooftops = Rooftops::from(
+      "..........
+..........
+..........
+....a.....
+..........
+.....a....
+..........
+..........
+..........
+..........",
+    );
+    let expected = "..........
+...#......
+..........
+....a.....
+..........
+.....a....
+..........
+......#...
+..........
+..........";
+    assert_eq!(expected, rooftops.render(AntinodeRule::Doubled, Some(b'a')));
+  }
+}
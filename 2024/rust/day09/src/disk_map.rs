@@ -0,0 +1,505 @@
+use std::{
+  cmp::Reverse,
+  collections::{BTreeMap, BinaryHeap},
+  fmt::Display,
+};
+
+// Sizes come from single digits in the disk map, so no file is ever longer than 9.
+// Free spans can grow past that once files have been moved out of the way
+const MAX_SPAN: usize = 9;
+
+// A run of contiguous blocks on the disk, all belonging to the same file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Extent {
+  pub offset: usize,
+  pub length: usize,
+  pub id: usize,
+}
+
+impl Extent {
+  // Sum of id * position over every block in the extent, without walking the blocks:
+  // the positions offset..offset+length form an arithmetic series
+  pub fn checksum(&self) -> u128 {
+    let first = self.offset as u128;
+    let length = self.length as u128;
+    // sum(first..first+length) = length * first + length * (length - 1) / 2
+    self.id as u128 * (length * first + length * length.saturating_sub(1) / 2)
+  }
+
+  fn end(&self) -> usize {
+    self.offset + self.length
+  }
+}
+
+// How files get moved towards the front of the disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+  // Part 1: move single blocks from the end of the disk into the leftmost free block,
+  // splitting files across as many free spans as it takes
+  BlockByBlock,
+  // Part 2: move whole files, highest id first, into the leftmost free span that fits
+  WholeFile,
+  // Move whole files into the smallest free span that fits, leftmost on a tie
+  BestFit,
+  // Move whole files into the largest free span that fits, leftmost on a tie
+  WorstFit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compaction {
+  pub checksum: u128,
+  // Free spans left stranded between files once compaction is done
+  pub fragmentation: usize,
+  // Number of extents relocated - a file split over three free spans is three moves
+  pub moves: usize,
+}
+
+// Free spans bucketed by their exact length, each bucket a min-heap on offset,
+// so the leftmost span that can hold n blocks is the best of buckets n and up.
+// Only the lengths actually free get a bucket, and most disks only have 1 to 9
+#[derive(Debug, Default)]
+struct FreeIndex {
+  buckets: BTreeMap<usize, BinaryHeap<Reverse<usize>>>,
+}
+
+impl FreeIndex {
+  fn insert(&mut self, offset: usize, length: usize) {
+    if length > 0 {
+      self
+        .buckets
+        .entry(length)
+        .or_default()
+        .push(Reverse(offset));
+    }
+  }
+
+  // Take `length` blocks from the front of the leftmost span in a bucket,
+  // putting whatever is left of the span back in the index
+  fn take(&mut self, size: usize, length: usize) -> Option<usize> {
+    let bucket = self.buckets.get_mut(&size)?;
+    let Reverse(offset) = bucket.pop()?;
+    if bucket.is_empty() {
+      self.buckets.remove(&size);
+    }
+    self.insert(offset + length, size - length);
+    Some(offset)
+  }
+
+  // Pick a span of at least `length` blocks lying before `limit`
+  fn allocate(&mut self, strategy: Strategy, length: usize, limit: usize) -> Option<usize> {
+    let mut candidates = self.buckets.range(length..).filter_map(|(&size, bucket)| {
+      bucket
+        .peek()
+        .filter(|Reverse(offset)| *offset < limit)
+        .map(|Reverse(offset)| (size, *offset))
+    });
+    let (size, _) = match strategy {
+      Strategy::BestFit => candidates.next(),
+      Strategy::WorstFit => candidates.next_back(),
+      _ => candidates.min_by_key(|&(_, offset)| offset),
+    }?;
+    self.take(size, length)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiskMap {
+  // In offset order, which for a freshly read disk map is also id order
+  files: Vec<Extent>,
+  size: usize,
+}
+
+// One line of digits, with nothing else but the line ending
+impl TryFrom<&str> for DiskMap {
+  type Error = miette::Report;
+
+  fn try_from(input: &str) -> miette::Result<Self> {
+    let mut files = Vec::new();
+    let mut offset = 0;
+    // File and Free alternate in the input
+    for (idx, chr) in input.trim_end().chars().enumerate() {
+      let length = chr
+        .to_digit(10)
+        .ok_or_else(|| miette::miette!("Expected a digit, found {chr:?} at {idx}"))?
+        as usize;
+      if idx % 2 == 0 {
+        files.push(Extent {
+          offset,
+          length,
+          id: idx / 2,
+        });
+      }
+      offset += length;
+    }
+    Ok(Self {
+      files,
+      size: offset,
+    })
+  }
+}
+
+// The block view from the puzzle, with ids over 9 wrapping back round to 0
+impl Display for DiskMap {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let mut position = 0;
+    for file in self.extents() {
+      write!(f, "{}", ".".repeat(file.offset - position))?;
+      write!(f, "{}", (file.id % 10).to_string().repeat(file.length))?;
+      position = file.end();
+    }
+    write!(f, "{}", ".".repeat(self.size - position))
+  }
+}
+
+impl DiskMap {
+  pub fn files(&self) -> &[Extent] {
+    &self.files
+  }
+
+  pub fn checksum(&self) -> u128 {
+    self.files.iter().map(Extent::checksum).sum()
+  }
+
+  // Files that take up any blocks. An empty file splits no free span, and once
+  // files have moved it can even sit inside one of them
+  fn extents(&self) -> impl Iterator<Item = &Extent> + '_ {
+    self.files.iter().filter(|file| file.length > 0)
+  }
+
+  // Free spans between and after the files as (offset, length), in offset order
+  fn gaps(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let ends = std::iter::once(0).chain(self.extents().map(Extent::end));
+    let starts = self
+      .extents()
+      .map(|file| file.offset)
+      .chain(std::iter::once(self.size));
+    ends.zip(starts).map(|(end, start)| (end, start - end))
+  }
+
+  // Free spans with a file somewhere after them
+  pub fn fragmentation(&self) -> usize {
+    self
+      .gaps()
+      .take(self.extents().count())
+      .filter(|&(_, length)| length > 0)
+      .count()
+  }
+
+  // Write the disk back out in the dense format. The format can't carry file ids,
+  // so files come back numbered in the order they sit on the disk. Free spans longer
+  // than 9 blocks are broken up with empty files
+  pub fn encode(&self) -> String {
+    fn push_free(dense: &mut String, mut free: usize) {
+      while free > MAX_SPAN {
+        dense.push_str(&format!("{MAX_SPAN}0"));
+        free -= MAX_SPAN;
+      }
+      dense.push_str(&free.to_string());
+    }
+
+    let mut dense = String::new();
+    let mut gaps = self.gaps().map(|(_, length)| length);
+    // The dense format always starts with a file, so leading space needs an empty one
+    if let Some(leading) = gaps.next().filter(|&leading| leading > 0) {
+      dense.push('0');
+      push_free(&mut dense, leading);
+    }
+    let files = self.extents().count();
+    for (idx, (file, free)) in self.extents().zip(gaps).enumerate() {
+      dense.push_str(&file.length.to_string());
+      if free > 0 || idx + 1 < files {
+        push_free(&mut dense, free);
+      }
+    }
+    dense
+  }
+
+  pub fn compact(mut self, strategy: Strategy) -> (Self, Compaction) {
+    let moves = match strategy {
+      Strategy::BlockByBlock => self.compact_blocks(),
+      _ => self.compact_files(strategy),
+    };
+    self.files.sort_unstable_by_key(|file| file.offset);
+    let compaction = Compaction {
+      checksum: self.checksum(),
+      fragmentation: self.fragmentation(),
+      moves,
+    };
+    (self, compaction)
+  }
+
+  // Fill free spans left to right with blocks taken from the back of the disk.
+  // Space vacated by a move is never reused: it's always to the right of the
+  // files still to be moved
+  fn compact_blocks(&mut self) -> usize {
+    let mut free: Vec<(usize, usize)> = self.gaps().filter(|&(_, length)| length > 0).collect();
+    let mut next_free = 0;
+    let mut compacted = Vec::with_capacity(self.files.len());
+    let mut moves = 0;
+    for file in self.files.iter().rev() {
+      let mut remaining = file.length;
+      while remaining > 0 && next_free < free.len() && free[next_free].0 < file.offset {
+        let (offset, length) = &mut free[next_free];
+        let moved = remaining.min(*length);
+        compacted.push(Extent {
+          offset: *offset,
+          length: moved,
+          id: file.id,
+        });
+        *offset += moved;
+        *length -= moved;
+        remaining -= moved;
+        moves += 1;
+        if *length == 0 {
+          next_free += 1;
+        }
+      }
+      if remaining > 0 {
+        compacted.push(Extent {
+          length: remaining,
+          ..*file
+        });
+      }
+    }
+    self.files = compacted;
+    moves
+  }
+
+  // Move each whole file, highest id first, to a free span chosen by the strategy.
+  // Like block compaction, files only ever move left so vacated space is never reused
+  fn compact_files(&mut self, strategy: Strategy) -> usize {
+    let mut free = FreeIndex::default();
+    for (offset, length) in self.gaps() {
+      free.insert(offset, length);
+    }
+    let mut moves = 0;
+    self.files.sort_unstable_by_key(|file| Reverse(file.id));
+    // An empty file has nothing to move
+    for file in self.files.iter_mut().filter(|file| file.length > 0) {
+      if let Some(offset) = free.allocate(strategy, file.length, file.offset) {
+        file.offset = offset;
+        moves += 1;
+      }
+    }
+    moves
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  // proptest has a Strategy of its own
+  use proptest::{
+    prelude::{prop, prop_assert, prop_assert_eq, prop_oneof, proptest, Just, ProptestConfig},
+    strategy::Strategy as _,
+    test_runner::RngSeed,
+  };
+  use rstest::rstest;
+
+  const INPUT: &str = "2333133121414131402";
+
+  #[test]
+  fn test_extent_checksum() {
+    // blocks 2, 3, 4 of file 5 => 5 * (2 + 3 + 4)
+    let extent = Extent {
+      offset: 2,
+      length: 3,
+      id: 5,
+    };
+    assert_eq!(45, extent.checksum());
+  }
+
+  #[rstest]
+  #[case(Strategy::BlockByBlock, "0099811188827773336446555566..............")]
+  #[case(Strategy::WholeFile, "00992111777.44.333....5555.6666.....8888..")]
+  fn test_render(#[case] strategy: Strategy, #[case] expected: &str) {
+    let disk = DiskMap::try_from(INPUT).unwrap();
+    assert_eq!(
+      "00...111...2...333.44.5555.6666.777.888899",
+      disk.to_string()
+    );
+    assert_eq!(expected, disk.compact(strategy).0.to_string());
+  }
+
+  #[rstest]
+  #[case(Strategy::BlockByBlock, 1928, 0)]
+  #[case(Strategy::WholeFile, 2858, 5)]
+  fn test_compact(
+    #[case] strategy: Strategy,
+    #[case] checksum: u128,
+    #[case] fragmentation: usize,
+  ) {
+    let (_, compaction) = DiskMap::try_from(INPUT).unwrap().compact(strategy);
+    assert_eq!(checksum, compaction.checksum);
+    assert_eq!(fragmentation, compaction.fragmentation);
+  }
+
+  #[test]
+  fn test_fit_strategies() {
+    // File 2 fits in either gap: best fit takes the small one, worst fit the big one,
+    // and file 1 is too big to go anywhere
+    let disk = DiskMap::try_from("11931").unwrap();
+    let (best, compaction) = disk.clone().compact(Strategy::BestFit);
+    assert_eq!("02111111111....", best.to_string());
+    assert_eq!(1, compaction.moves);
+    let (worst, compaction) = disk.compact(Strategy::WorstFit);
+    assert_eq!("0.1111111112...", worst.to_string());
+    assert_eq!(1, compaction.moves);
+  }
+
+  #[test]
+  fn test_fit_long_spans() {
+    // Spans past 9 blocks only turn up once compaction has merged some,
+    // and still have to be told apart by length
+    let mut free = FreeIndex::default();
+    free.insert(0, 12);
+    free.insert(20, 10);
+    assert_eq!(Some(20), free.allocate(Strategy::BestFit, 3, 100));
+    assert_eq!(Some(0), free.allocate(Strategy::WorstFit, 3, 100));
+    // 9 blocks left at 3 and 7 at 23, then 2 at 10 and 7 at 23
+    assert_eq!(Some(3), free.allocate(Strategy::WorstFit, 7, 100));
+    assert_eq!(Some(10), free.allocate(Strategy::BestFit, 2, 100));
+  }
+
+  #[test]
+  fn test_empty_files_stay_put() {
+    // Only file 2 moves, empty file 1 isn't a move
+    let (disk, compaction) = DiskMap::try_from("12011")
+      .unwrap()
+      .compact(Strategy::WholeFile);
+    assert_eq!("02...", disk.to_string());
+    assert_eq!(1, compaction.moves);
+  }
+
+  #[test]
+  fn test_encode_round_trip() {
+    let disk = DiskMap::try_from(INPUT).unwrap();
+    assert_eq!(INPUT, disk.encode());
+    let (compacted, _) = disk.compact(Strategy::BlockByBlock);
+    let reread = DiskMap::try_from(compacted.encode().as_str()).unwrap();
+    assert_eq!(compacted.encode(), reread.encode());
+    // ids get renumbered, but the layout survives
+    let layout = |disk: &DiskMap| disk.to_string().replace(|c: char| c.is_ascii_digit(), "#");
+    assert_eq!(layout(&compacted), layout(&reread));
+  }
+
+  #[test]
+  fn test_empty_file_under_a_moved_one() {
+    // Empty file 1 doesn't split the free span at 3, so file 3 moves over it,
+    // and empty file 4 doesn't make the space before it a gap between files
+    let (disk, compaction) = DiskMap::try_from("3108739808")
+      .unwrap()
+      .compact(Strategy::WholeFile);
+    assert_eq!(
+      "0003333333332222222............................",
+      disk.to_string()
+    );
+    assert_eq!(399, compaction.checksum);
+    assert_eq!(0, compaction.fragmentation);
+  }
+
+  #[test]
+  fn test_encode_long_free_span() {
+    let disk = DiskMap::try_from("1991").unwrap();
+    let (compacted, _) = disk.compact(Strategy::WholeFile);
+    assert_eq!("0111111111..........", compacted.to_string());
+    assert_eq!("109901", compacted.encode());
+    assert_eq!(
+      compacted.to_string(),
+      DiskMap::try_from("109901").unwrap().to_string()
+    );
+  }
+
+  #[test]
+  fn test_compact_files_large_disk() {
+    // ~2MB disk map of single-block files each followed by nine free blocks,
+    // which compacts down to every file packed at the front of the disk
+    let input = "19".repeat(1_000_000);
+    let (disk, compaction) = DiskMap::try_from(input.as_str())
+      .unwrap()
+      .compact(Strategy::WholeFile);
+    assert_eq!(0, compaction.fragmentation);
+    assert!(disk.files().iter().map(|file| file.offset).eq(0..1_000_000));
+  }
+
+  // Dense disk maps of up to 30 files
+  fn dense(file_lengths: std::ops::Range<u8>) -> impl proptest::strategy::Strategy<Value = String> {
+    prop::collection::vec((file_lengths, 0..10u8), 1..30).prop_map(|files| {
+      files
+        .iter()
+        .flat_map(|&(file, free)| [file, free])
+        .map(|digit| char::from(b'0' + digit))
+        .collect()
+    })
+  }
+
+  proptest! {
+    #![proptest_config(ProptestConfig {
+      rng_seed: RngSeed::Fixed(9),
+      ..ProptestConfig::default()
+    })]
+
+    // However the files get moved, every block of every file is still on the
+    // disk and nothing overlaps
+    #[test]
+    fn test_compaction_keeps_files(
+      dense in dense(0..10),
+      strategy in prop_oneof![
+        Just(Strategy::BlockByBlock),
+        Just(Strategy::WholeFile),
+        Just(Strategy::BestFit),
+        Just(Strategy::WorstFit),
+      ],
+    ) {
+      let disk = DiskMap::try_from(dense.as_str()).unwrap();
+      // Block compaction drops empty files, so only count the ones with blocks
+      let blocks = |disk: &DiskMap| {
+        let mut blocks = BTreeMap::new();
+        for file in disk.extents() {
+          *blocks.entry(file.id).or_insert(0) += file.length;
+        }
+        blocks
+      };
+      let (compacted, _) = disk.clone().compact(strategy);
+      prop_assert_eq!(blocks(&disk), blocks(&compacted));
+
+      let mut extents: Vec<Extent> = compacted.extents().copied().collect();
+      extents.sort_unstable_by_key(|extent| extent.offset);
+      prop_assert!(extents.windows(2).all(|pair| pair[0].offset + pair[0].length <= pair[1].offset));
+      prop_assert!(extents.last().is_none_or(|last| last.offset + last.length <= disk.size));
+    }
+
+    // Replaying the moves highest id first, no file ever lands to the right of
+    // a free span it would have fitted in when its turn came
+    #[test]
+    fn test_whole_files_take_the_leftmost_fit(dense in dense(0..10)) {
+      let disk = DiskMap::try_from(dense.as_str()).unwrap();
+      let (compacted, _) = disk.clone().compact(Strategy::WholeFile);
+      let mut blocks = vec![None; disk.size];
+      for file in disk.extents() {
+        blocks[file.offset..file.end()].fill(Some(file.id));
+      }
+      let mut moved: Vec<&Extent> = compacted.extents().collect();
+      moved.sort_unstable_by_key(|file| Reverse(file.id));
+      for file in moved {
+        let before = &blocks[..file.offset];
+        let fits = before
+          .split(Option::is_some)
+          .any(|span| span.len() >= file.length);
+        prop_assert!(!fits, "file {} could have moved left of {}", file.id, file.offset);
+        blocks.iter_mut().filter(|block| **block == Some(file.id)).for_each(|block| *block = None);
+        blocks[file.offset..file.end()].fill(Some(file.id));
+      }
+    }
+
+    // With every file a single block, moving blocks and moving whole files
+    // are the same thing
+    #[test]
+    fn test_single_blocks_agree(dense in dense(1..2)) {
+      let disk = DiskMap::try_from(dense.as_str()).unwrap();
+      let (blocks, _) = disk.clone().compact(Strategy::BlockByBlock);
+      let (files, _) = disk.compact(Strategy::WholeFile);
+      prop_assert_eq!(blocks.to_string(), files.to_string());
+    }
+  }
+}
@@ -0,0 +1,36 @@
+use day10::{
+  render::Render,
+  trails::{Map, StepRule},
+};
+use miette::{miette, Context, IntoDiagnostic};
+
+// Usage: visualise <input file> [trailhead index] [svg file]
+fn main() -> miette::Result<()> {
+  tracing_subscriber::fmt::init();
+  let mut args = std::env::args().skip(1);
+  let path = args
+    .next()
+    .ok_or_else(|| miette!("usage: visualise <input file> [trailhead index] [svg file]"))?;
+  let input = std::fs::read_to_string(&path)
+    .into_diagnostic()
+    .wrap_err_with(|| format!("reading {path}"))?;
+  let map = Map::from(input.as_str());
+
+  let mut render = Render::new(&map);
+  if let Some(index) = args.next() {
+    let index: usize = index.parse().into_diagnostic().context("trailhead index")?;
+    let start = *map
+      .trail_heads
+      .get(index)
+      .ok_or_else(|| miette!("only {} trailheads on this map", map.trail_heads.len()))?;
+    render = render.highlight_trails(start, StepRule::default());
+  }
+
+  print!("{}", render.ansi());
+  if let Some(svg_path) = args.next() {
+    std::fs::write(&svg_path, render.svg())
+      .into_diagnostic()
+      .wrap_err_with(|| format!("writing {svg_path}"))?;
+  }
+  Ok(())
+}
@@ -1,2 +1,7 @@
 pub mod part1;
 pub mod part2;
+pub mod render;
+pub mod trails;
+
+#[cfg(test)]
+generators::fuzz_tests!(10);
@@ -0,0 +1,164 @@
+use std::{collections::HashSet, fmt::Write};
+
+use crate::trails::{Map, Position, StepRule};
+
+const CELL: usize = 24;
+
+type Rgb = (u8, u8, u8);
+
+const LOWLAND: Rgb = (0x1b, 0x5e, 0x20);
+const SUMMIT: Rgb = (0xf5, 0xf5, 0xdc);
+const TRAIL: Rgb = (0xe6, 0x39, 0x46);
+
+// Blend from green valleys at 0 up to pale summits at 9
+fn gradient(height: u32) -> Rgb {
+  let blend = |low: u8, high: u8| {
+    let (low, high) = (low as u32, high as u32);
+    ((low * (9 - height.min(9)) + high * height.min(9)) / 9) as u8
+  };
+  (
+    blend(LOWLAND.0, SUMMIT.0),
+    blend(LOWLAND.1, SUMMIT.1),
+    blend(LOWLAND.2, SUMMIT.2),
+  )
+}
+
+pub struct Render<'a> {
+  map: &'a Map,
+  highlight: HashSet<Position>,
+}
+
+impl<'a> Render<'a> {
+  pub fn new(map: &'a Map) -> Self {
+    Self {
+      map,
+      highlight: HashSet::new(),
+    }
+  }
+
+  // Mark every cell on any trail from start to a peak
+  pub fn highlight_trails(mut self, start: Position, rule: StepRule) -> Self {
+    self.highlight.extend(self.map.trail_cells(start, rule));
+    self
+  }
+
+  fn cells(&self) -> impl Iterator<Item = (Position, u32)> + '_ {
+    self.map.terrain.iter().enumerate().flat_map(|(x, row)| {
+      row
+        .iter()
+        .enumerate()
+        .map(move |(y, &height)| ((x as i32, y as i32), height))
+    })
+  }
+
+  // Heights on a 24-bit colour background, trail cells picked out in bold red
+  pub fn ansi(&self) -> String {
+    let mut out = String::new();
+    let mut row = 0;
+    for ((x, y), height) in self.cells() {
+      // Start a new line for each row, even ones with nothing in them
+      for _ in row..x {
+        out.push('\n');
+      }
+      row = x;
+      let (r, g, b) = gradient(height);
+      let _ = write!(out, "\x1b[48;2;{r};{g};{b}m");
+      if self.highlight.contains(&(x, y)) {
+        let (r, g, b) = TRAIL;
+        let _ = write!(out, "\x1b[1;38;2;{r};{g};{b}m");
+      } else {
+        let _ = write!(out, "\x1b[38;2;0;0;0m");
+      }
+      let _ = write!(out, "{height}\x1b[0m");
+    }
+    for _ in row..self.map.terrain.len() as i32 {
+      out.push('\n');
+    }
+    out
+  }
+
+  pub fn svg(&self) -> String {
+    let height = self.map.terrain.len() * CELL;
+    let width = self
+      .map
+      .terrain
+      .iter()
+      .map(Vec::len)
+      .max()
+      .unwrap_or_default()
+      * CELL;
+    let mut out = String::new();
+    let _ = writeln!(
+      out,
+      r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" font-family="monospace" font-size="{}">"#,
+      CELL / 2
+    );
+    for ((x, y), cell_height) in self.cells() {
+      let (left, top) = (y as usize * CELL, x as usize * CELL);
+      let (r, g, b) = gradient(cell_height);
+      let _ = writeln!(
+        out,
+        r#"<rect x="{left}" y="{top}" width="{CELL}" height="{CELL}" fill="rgb({r},{g},{b})"/>"#
+      );
+      if self.highlight.contains(&(x, y)) {
+        let (r, g, b) = TRAIL;
+        let _ = writeln!(
+          out,
+          r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="rgb({r},{g},{b})" stroke-width="3"/>"#,
+          left + 2,
+          top + 2,
+          CELL - 4,
+          CELL - 4
+        );
+      }
+      let _ = writeln!(
+        out,
+        r#"<text x="{}" y="{}" text-anchor="middle" dominant-baseline="central">{cell_height}</text>"#,
+        left + CELL / 2,
+        top + CELL / 2
+      );
+    }
+    out.push_str("</svg>\n");
+    out
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const INPUT: &str = "0123
+1234
+8765
+9876";
+
+  #[test]
+  fn test_highlight_trails() {
+    let map = Map::from("0123\n9994\n9995\n9876");
+    let render = Render::new(&map).highlight_trails((0, 0), StepRule::default());
+    // The outer ring climbs to the 9 in the corner, and the 8 also steps up to the 9 above it
+    assert_eq!(11, render.highlight.len());
+    assert!(!render.highlight.contains(&(1, 1)));
+  }
+
+  #[test]
+  fn test_ansi() {
+    let map = Map::from("01\n\n23");
+    let ansi = Render::new(&map).ansi();
+    // The empty row is kept
+    assert_eq!(3, ansi.lines().count());
+    assert_eq!("", ansi.lines().nth(1).unwrap());
+    assert_eq!(4, ansi.matches("\x1b[0m").count());
+  }
+
+  #[test]
+  fn test_svg() {
+    let map = Map::from(INPUT);
+    let svg = Render::new(&map)
+      .highlight_trails((0, 0), StepRule::default())
+      .svg();
+    assert!(svg.starts_with("<svg"));
+    assert_eq!(16, svg.matches("<text").count());
+    assert_eq!(16 + 16, svg.matches("<rect").count());
+  }
+}
@@ -0,0 +1,288 @@
+use std::{
+  cmp::Reverse,
+  collections::{HashMap, HashSet},
+};
+
+const ORTHOGONAL: [(i32, i32); 4] = [(0, 1), (0, -1), (1, 0), (-1, 0)];
+const DIAGONAL: [(i32, i32); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+const PEAK: u32 = 9;
+
+pub type Position = (i32, i32);
+
+// What counts as a single step along a trail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepRule {
+  // How much higher the next step has to be. A delta of 0 never climbs to a peak,
+  // so it finds no trails at all
+  pub delta: u32,
+  // Allow moving diagonally as well as up, down, left and right
+  pub diagonal: bool,
+}
+
+impl Default for StepRule {
+  fn default() -> Self {
+    Self {
+      delta: 1,
+      diagonal: false,
+    }
+  }
+}
+
+impl StepRule {
+  fn offsets(self) -> impl Iterator<Item = (i32, i32)> {
+    let diagonals: &[(i32, i32)] = if self.diagonal { &DIAGONAL } else { &[] };
+    ORTHOGONAL.iter().chain(diagonals).copied()
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trailhead {
+  pub start: Position,
+  // Distinct peaks reachable from the trailhead
+  pub score: usize,
+  // Distinct trails from the trailhead to any peak
+  pub rating: u64,
+  // Every trail, start to peak, when asked for
+  pub paths: Option<Vec<Vec<Position>>>,
+}
+
+#[derive(Debug)]
+pub struct Map {
+  pub terrain: Vec<Vec<u32>>,
+  pub trail_heads: Vec<Position>,
+}
+
+impl From<&str> for Map {
+  fn from(input: &str) -> Self {
+    let terrain: Vec<Vec<u32>> = input
+      .lines()
+      .map(|line| line.chars().filter_map(|ch| ch.to_digit(10)).collect())
+      .collect();
+
+    // trails start at each 0 on the terrain
+    let trail_heads = terrain
+      .iter()
+      .enumerate()
+      .flat_map(|(x, row)| {
+        row
+          .iter()
+          .enumerate()
+          .filter_map(move |(y, &item)| (item == 0).then_some((x as i32, y as i32)))
+      })
+      .collect();
+
+    Self {
+      terrain,
+      trail_heads,
+    }
+  }
+}
+
+// Memo tables filled in from the peaks down, so each cell is worked out once
+// however many trailheads lead through it
+struct Climb {
+  ratings: HashMap<Position, u64>,
+  peaks: HashMap<Position, HashSet<Position>>,
+}
+
+impl Map {
+  // Locate a point on the terrain by grid indexes
+  // Return Some(value) or None if outside the bounds of the grid
+  pub fn get(&self, (x, y): Position) -> Option<u32> {
+    self.terrain.get(x as usize)?.get(y as usize).copied()
+  }
+
+  // Neighbouring positions that are one valid step up from here
+  pub fn next_steps(
+    &self,
+    (x, y): Position,
+    rule: StepRule,
+  ) -> impl Iterator<Item = Position> + '_ {
+    let height = self.get((x, y));
+    rule
+      .offsets()
+      .map(move |(offset_x, offset_y)| (x + offset_x, y + offset_y))
+      .filter(move |&next| {
+        matches!((height, self.get(next)), (Some(height), Some(next)) if next == height + rule.delta)
+      })
+  }
+
+  fn climb(&self, rule: StepRule) -> Climb {
+    let mut ratings = HashMap::new();
+    let mut peaks = HashMap::new();
+    if rule.delta == 0 {
+      return Climb { ratings, peaks };
+    }
+
+    let mut cells: Vec<(u32, Position)> = self
+      .terrain
+      .iter()
+      .enumerate()
+      .flat_map(|(x, row)| {
+        row
+          .iter()
+          .enumerate()
+          .map(move |(y, &height)| (height, (x as i32, y as i32)))
+      })
+      .collect();
+    // Highest first, so every step up has been worked out before we need it
+    cells.sort_unstable_by_key(|&(height, _)| Reverse(height));
+
+    for (height, position) in cells {
+      let (rating, reachable) = if height == PEAK {
+        (1, HashSet::from([position]))
+      } else {
+        self.next_steps(position, rule).fold(
+          (0, HashSet::new()),
+          |(rating, mut reachable), next| {
+            reachable.extend(&peaks[&next]);
+            (rating + ratings[&next], reachable)
+          },
+        )
+      };
+      ratings.insert(position, rating);
+      peaks.insert(position, reachable);
+    }
+    Climb { ratings, peaks }
+  }
+
+  // Walk every trail from start, only stepping onto cells that still lead to a peak
+  fn paths(&self, start: Position, rule: StepRule, climb: &Climb) -> Vec<Vec<Position>> {
+    let mut paths = vec![];
+    let mut stack = vec![vec![start]];
+    while let Some(path) = stack.pop() {
+      let here = *path.last().unwrap();
+      if self.get(here) == Some(PEAK) {
+        paths.push(path);
+        continue;
+      }
+      for next in self.next_steps(here, rule) {
+        if climb.ratings[&next] > 0 {
+          let mut longer = path.clone();
+          longer.push(next);
+          stack.push(longer);
+        }
+      }
+    }
+    paths
+  }
+
+  // Every cell on some trail from a trailhead to a peak. Each cell is visited
+  // once, so there's no need to list the trails themselves
+  pub fn trail_cells(&self, start: Position, rule: StepRule) -> HashSet<Position> {
+    let climb = self.climb(rule);
+    let leads_up = |position: &Position| {
+      climb
+        .ratings
+        .get(position)
+        .is_some_and(|&rating| rating > 0)
+    };
+    let mut cells = HashSet::new();
+    if !self.trail_heads.contains(&start) || !leads_up(&start) {
+      return cells;
+    }
+    cells.insert(start);
+    let mut stack = vec![start];
+    while let Some(here) = stack.pop() {
+      for next in self.next_steps(here, rule) {
+        if leads_up(&next) && cells.insert(next) {
+          stack.push(next);
+        }
+      }
+    }
+    cells
+  }
+
+  pub fn trails(&self, rule: StepRule, with_paths: bool) -> Vec<Trailhead> {
+    let climb = self.climb(rule);
+    self
+      .trail_heads
+      .iter()
+      .map(|&start| Trailhead {
+        start,
+        score: climb.peaks.get(&start).map_or(0, HashSet::len),
+        rating: climb.ratings.get(&start).copied().unwrap_or_default(),
+        paths: (with_paths && rule.delta > 0).then(|| self.paths(start, rule, &climb)),
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use itertools::Itertools;
+
+  #[test]
+  fn test_single_trailhead() {
+    let map = Map::from(
+      "0123
+1234
+8765
+9876",
+    );
+    let trails = map.trails(StepRule::default(), true);
+    assert_eq!(1, trails.len());
+    assert_eq!(1, trails[0].score);
+    assert_eq!(16, trails[0].rating);
+    let paths = trails[0].paths.as_ref().unwrap();
+    assert_eq!(16, paths.len());
+    assert!(paths
+      .iter()
+      .all(|path| path.len() == 10 && path[0] == (0, 0) && path[9] == (3, 0)));
+  }
+
+  #[test]
+  fn test_trail_cells() {
+    let map = Map::from(
+      "89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732",
+    );
+    // The same cells as walking every trail, for every trailhead
+    for trailhead in map.trails(StepRule::default(), true) {
+      let walked: HashSet<Position> = trailhead.paths.unwrap().into_iter().flatten().collect();
+      assert_eq!(
+        walked,
+        map.trail_cells(trailhead.start, StepRule::default())
+      );
+    }
+    // Only trailheads start trails
+    assert!(map.trail_cells((0, 0), StepRule::default()).is_empty());
+  }
+
+  #[test]
+  fn test_step_rules() {
+    let map = Map::from(
+      "0369
+3000
+6000
+9000",
+    );
+    let rule = StepRule {
+      delta: 3,
+      diagonal: false,
+    };
+    let trails = map.trails(rule, false);
+    assert_eq!(2, trails[0].score);
+    assert_eq!(None, trails[0].paths);
+    assert_eq!(0, map.trails(StepRule::default(), false)[0].score);
+
+    // 0 to 9 down the diagonal of a grid of 0s
+    let input = (0..10)
+      .map(|x| (0..10).map(|y| if x == y { x } else { 0 }).join(""))
+      .join("\n");
+    let map = Map::from(input.as_str());
+    let rule = StepRule {
+      delta: 1,
+      diagonal: true,
+    };
+    assert_eq!(0, map.trails(StepRule::default(), false)[0].rating);
+    assert_eq!(1, map.trails(rule, false)[0].rating);
+  }
+}
@@ -0,0 +1,245 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::stones::{MagicStones, StoneRules};
+
+// Square matrix of counts modulo some prime, stored row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Matrix {
+  size: usize,
+  modulus: u64,
+  cells: Vec<u64>,
+}
+
+impl Matrix {
+  fn zero(size: usize, modulus: u64) -> Self {
+    Self {
+      size,
+      modulus,
+      cells: vec![0; size * size],
+    }
+  }
+
+  fn add(&mut self, row: usize, col: usize, value: u64) {
+    let cell = &mut self.cells[row * self.size + col];
+    *cell = (*cell + value) % self.modulus;
+  }
+
+  fn mul(&self, other: &Self) -> Self {
+    let mut product = Self::zero(self.size, self.modulus);
+    let modulus = self.modulus as u128;
+    for row in 0..self.size {
+      let mut acc = vec![0u128; self.size];
+      for k in 0..self.size {
+        let a = self.cells[row * self.size + k] as u128;
+        if a == 0 {
+          continue;
+        }
+        for (col, cell) in acc.iter_mut().enumerate() {
+          *cell = (*cell + a * other.cells[k * self.size + col] as u128) % modulus;
+        }
+      }
+      for (col, cell) in acc.into_iter().enumerate() {
+        product.cells[row * self.size + col] = cell as u64;
+      }
+    }
+    product
+  }
+
+  fn apply(&self, vector: &[u64]) -> Vec<u64> {
+    let modulus = self.modulus as u128;
+    (0..self.size)
+      .map(|row| {
+        let total = vector
+          .iter()
+          .enumerate()
+          .map(|(col, &v)| self.cells[row * self.size + col] as u128 * v as u128 % modulus)
+          .sum::<u128>();
+        (total % modulus) as u64
+      })
+      .collect()
+  }
+}
+
+// How a line of stones grows until every value it can ever show has turned up
+#[derive(Debug, Clone)]
+pub struct Analysis {
+  stones: MagicStones,
+  // What each value seen so far turns into on the next blink
+  transitions: HashMap<u64, Vec<u64>>,
+  // Distinct values on the line after each blink, starting with the line as given
+  pub distinct: Vec<usize>,
+  // The first blink by which the values seen so far only ever turn into each other
+  pub closed_at: Option<usize>,
+}
+
+impl Analysis {
+  // Blink up to max_blinks times, or until the set of values closes.
+  // None if a stone grows too big for a u64 along the way. Only which values
+  // are on the line matters here, so how many of each can't overflow
+  pub fn new(
+    rules: &impl StoneRules<u64>,
+    stones: &MagicStones,
+    max_blinks: usize,
+  ) -> Option<Self> {
+    let mut transitions: HashMap<u64, Vec<u64>> = HashMap::new();
+    // Values seen for the first time on the latest blink
+    let mut frontier: Vec<u64> = stones.stones().copied().collect();
+    let mut line: HashSet<u64> = stones.stones().copied().collect();
+    let mut distinct = vec![line.len()];
+    let mut closed_at = None;
+
+    for blink in 0..=max_blinks {
+      let mut next = vec![];
+      for stone in frontier {
+        let children = rules.blink(&stone)?;
+        transitions.insert(stone, children.clone());
+        next.extend(children);
+      }
+      next.sort_unstable();
+      next.dedup();
+      next.retain(|stone| !transitions.contains_key(stone));
+      if next.is_empty() {
+        closed_at = Some(blink);
+        break;
+      }
+      if blink == max_blinks {
+        break;
+      }
+      frontier = next;
+      // Every value on the line has been seen, so its transitions are known
+      line = line
+        .iter()
+        .flat_map(|stone| transitions[stone].iter().copied())
+        .collect();
+      distinct.push(line.len());
+    }
+
+    Some(Self {
+      stones: stones.clone(),
+      transitions,
+      distinct,
+      closed_at,
+    })
+  }
+
+  // Every value the line can ever show, once it has closed
+  pub fn values(&self) -> Option<impl Iterator<Item = &u64> + '_> {
+    self.closed_at.map(|_| self.transitions.keys())
+  }
+
+  // Number of stones after any number of blinks, modulo a prime, by raising the
+  // transition matrix of the closed value set to the power of the blinks.
+  // The matrix is dense, so this is best kept to closed sets of a few hundred values.
+  // None before the set closes, or for a modulus below 2
+  pub fn count_mod(&self, blinks: u64, prime: u64) -> Option<u64> {
+    self.closed_at?;
+    if prime < 2 {
+      return None;
+    }
+    let index: HashMap<u64, usize> = self
+      .transitions
+      .keys()
+      .enumerate()
+      .map(|(idx, &stone)| (stone, idx))
+      .collect();
+
+    // transition[to][from] = how many `to` stones one `from` stone turns into
+    let mut transition = Matrix::zero(index.len(), prime);
+    for (from, children) in &self.transitions {
+      for to in children {
+        transition.add(index[to], index[from], 1);
+      }
+    }
+
+    let mut line = vec![0; index.len()];
+    for (stone, &count) in &self.stones.counter {
+      line[index[stone]] = count % prime;
+    }
+
+    let mut power = blinks;
+    while power > 0 {
+      if power & 1 == 1 {
+        line = transition.apply(&line);
+      }
+      power >>= 1;
+      if power > 0 {
+        transition = transition.mul(&transition);
+      }
+    }
+    Some(
+      line
+        .into_iter()
+        .fold(0, |total, count| (total + count) % prime),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::stones::Plutonian;
+
+  const PRIME: u64 = 1_000_000_007;
+
+  #[test]
+  fn test_closure() {
+    let stones: MagicStones = MagicStones::parse("0").unwrap();
+    let analysis = Analysis::new(&Plutonian, &stones, 100).unwrap();
+    assert_eq!(&[1, 1, 1, 2, 3, 3], &analysis.distinct[..6]);
+    assert!(analysis.closed_at.is_some());
+    assert_eq!(54, analysis.values().unwrap().count());
+
+    // Not enough blinks to see every value yet
+    let early = Analysis::new(&Plutonian, &stones, 5).unwrap();
+    assert_eq!(None, early.closed_at);
+    assert_eq!(None, early.count_mod(5, PRIME));
+  }
+
+  #[test]
+  fn test_count_mod() {
+    let stones: MagicStones = MagicStones::parse("125 17").unwrap();
+    let analysis = Analysis::new(&Plutonian, &stones, 100).unwrap();
+    assert_eq!(Some(22), analysis.count_mod(6, PRIME));
+    assert_eq!(Some(55312), analysis.count_mod(25, PRIME));
+    assert_eq!(Some(65601038650482 % PRIME), analysis.count_mod(75, PRIME));
+    assert_eq!(None, analysis.count_mod(75, 0));
+    assert_eq!(None, analysis.count_mod(75, 1));
+  }
+
+  #[test]
+  fn test_huge_line() {
+    // Counts past a u64 don't get in the way of finding the values
+    let stones = MagicStones {
+      counter: HashMap::from([(0, u64::MAX)]),
+    };
+    assert_eq!(None, stones.clone().blinks(&Plutonian, 4));
+    let analysis = Analysis::new(&Plutonian, &stones, 100).unwrap();
+    assert_eq!(54, analysis.values().unwrap().count());
+    assert_eq!(Some(u64::MAX % PRIME), analysis.count_mod(0, PRIME));
+  }
+
+  #[test]
+  fn test_count_mod_huge_blinks() {
+    let stones: MagicStones = MagicStones::parse("0").unwrap();
+    let analysis = Analysis::new(&Plutonian, &stones, 100).unwrap();
+
+    // Step the line blink by blink, modulo the prime
+    let blinks = 20_000;
+    let mut line: HashMap<u64, u64> = stones.counter.clone();
+    for _ in 0..blinks {
+      let mut next: HashMap<u64, u64> = HashMap::new();
+      for (stone, count) in line {
+        for child in Plutonian.blink(&stone).unwrap() {
+          let total = next.entry(child).or_default();
+          *total = (*total + count) % PRIME;
+        }
+      }
+      line = next;
+    }
+    let expected = line
+      .values()
+      .fold(0, |total, count| (total + count) % PRIME);
+    assert_eq!(Some(expected), analysis.count_mod(blinks, PRIME));
+    assert!(analysis.count_mod(1_000_000, PRIME).is_some());
+  }
+}
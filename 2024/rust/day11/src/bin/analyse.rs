@@ -0,0 +1,47 @@
+use day11::{
+  analysis::Analysis,
+  stones::{MagicStones, Plutonian},
+};
+use miette::{miette, Context, IntoDiagnostic};
+
+const USAGE: &str = "usage: analyse <input file> <blinks> <prime> [max blinks to closure]";
+
+fn main() -> miette::Result<()> {
+  tracing_subscriber::fmt::init();
+  let args: Vec<String> = std::env::args().skip(1).collect();
+  let [path, blinks, prime, rest @ ..] = args.as_slice() else {
+    return Err(miette!(USAGE));
+  };
+  let blinks: u64 = blinks.parse().into_diagnostic().context("blinks")?;
+  let prime: u64 = prime.parse().into_diagnostic().context("prime")?;
+  if prime < 2 {
+    return Err(miette!("the prime has to be at least 2, not {prime}"));
+  }
+  let max_blinks: usize = match rest.first() {
+    Some(max_blinks) => max_blinks.parse().into_diagnostic().context("max blinks")?,
+    None => 1_000,
+  };
+
+  let input = std::fs::read_to_string(path)
+    .into_diagnostic()
+    .wrap_err_with(|| format!("reading {path}"))?;
+  let stones: MagicStones = MagicStones::parse(&input)?;
+  let analysis = Analysis::new(&Plutonian, &stones, max_blinks)
+    .ok_or_else(|| miette!("stones grew too big for a u64"))?;
+
+  for (blink, distinct) in analysis.distinct.iter().enumerate() {
+    println!("blink {blink:>4}: {distinct} distinct values");
+  }
+  match analysis.closed_at {
+    Some(closed_at) => {
+      let values = analysis.values().map_or(0, Iterator::count);
+      println!("closed after {closed_at} blinks with {values} values");
+      let count = analysis
+        .count_mod(blinks, prime)
+        .ok_or_else(|| miette!("no count without a closed set"))?;
+      println!("{count} stones after {blinks} blinks, modulo {prime}");
+    }
+    None => println!("still open after {max_blinks} blinks"),
+  }
+  Ok(())
+}
@@ -1,2 +1,7 @@
+pub mod analysis;
 pub mod part1;
 pub mod part2;
+pub mod stones;
+
+#[cfg(test)]
+generators::fuzz_tests!(11);
@@ -0,0 +1,369 @@
+use std::{collections::HashMap, fmt::Debug, hash::Hash, str::FromStr};
+
+use miette::miette;
+
+use num::{
+  traits::{CheckedAdd, CheckedMul, Euclid},
+  BigUint, Integer, One, Zero,
+};
+
+// A number that can be engraved on a stone
+pub trait Stone: Clone + Debug + Eq + Hash + Zero + One + CheckedMul + From<u32> + FromStr {
+  fn digits(&self) -> u32;
+  // Cut the digits in two, e.g. 123456 -> 123 & 456
+  fn split(&self, right_digits: u32) -> (Self, Self);
+}
+
+macro_rules! primitive_stone {
+  ($($int:ty),*) => {$(
+    impl Stone for $int {
+      fn digits(&self) -> u32 {
+        self.checked_ilog10().unwrap_or(0) + 1
+      }
+
+      fn split(&self, right_digits: u32) -> (Self, Self) {
+        let divisor = (10 as $int).pow(right_digits);
+        Euclid::div_rem_euclid(self, &divisor)
+      }
+    }
+  )*};
+}
+
+primitive_stone!(u64, u128);
+
+impl Stone for BigUint {
+  fn digits(&self) -> u32 {
+    self.to_str_radix(10).len() as u32
+  }
+
+  fn split(&self, right_digits: u32) -> (Self, Self) {
+    self.div_rem(&BigUint::from(10u32).pow(right_digits))
+  }
+}
+
+// A tally of stones, with the overflow checks we need to count very long lines
+pub trait Count: Clone + Debug + Zero + One + CheckedAdd + CheckedMul {}
+
+impl<T: Clone + Debug + Zero + One + CheckedAdd + CheckedMul> Count for T {}
+
+// What a single stone turns into each time we blink,
+// or None if the new stones are too big for S
+pub trait StoneRules<S> {
+  fn blink(&self, stone: &S) -> Option<Vec<S>>;
+}
+
+// The rules engraved on the stones in the puzzle
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Plutonian;
+
+impl<S: Stone> StoneRules<S> for Plutonian {
+  fn blink(&self, stone: &S) -> Option<Vec<S>> {
+    if stone.is_zero() {
+      return Some(vec![S::one()]);
+    }
+    let digits = stone.digits();
+    if digits.is_multiple_of(2) {
+      let (left, right) = stone.split(digits / 2);
+      Some(vec![left, right])
+    } else {
+      Some(vec![stone.checked_mul(&S::from(2024))?])
+    }
+  }
+}
+
+// How many stones of each value are in the line. The order of the stones never
+// matters to the rules, so equal stones only need working out once per blink
+#[derive(Debug, Clone)]
+pub struct MagicStones<S = u64, C = u64> {
+  pub counter: HashMap<S, C>,
+}
+
+impl<S, C> Default for MagicStones<S, C> {
+  fn default() -> Self {
+    Self {
+      counter: HashMap::new(),
+    }
+  }
+}
+
+impl<S: Stone, C: Count + PartialEq> PartialEq for MagicStones<S, C> {
+  fn eq(&self, other: &Self) -> bool {
+    self.counter == other.counter
+  }
+}
+
+impl<S: Stone, C: Count> MagicStones<S, C> {
+  // Numbers split up by whitespace, and nothing else. Err if one of them is
+  // too big for S, or there are more of one than C can count
+  pub fn parse(input: &str) -> miette::Result<Self> {
+    let mut counter: HashMap<S, C> = HashMap::default();
+    for stone in input.split_whitespace() {
+      if !stone.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(miette!("Expected a stone number, found {stone:?}"));
+      }
+      let number = stone
+        .parse()
+        .map_err(|_| miette!("Stone {stone} is too big"))?;
+      let count = counter.entry(number).or_insert_with(C::zero);
+      *count = count
+        .checked_add(&C::one())
+        .ok_or_else(|| miette!("Too many {stone} stones to count"))?;
+    }
+    Ok(Self { counter })
+  }
+
+  // The same line with room for bigger stones or counts
+  pub fn widen<T: Stone + From<S>, D: Count + From<C>>(self) -> MagicStones<T, D> {
+    MagicStones {
+      counter: self
+        .counter
+        .into_iter()
+        .map(|(stone, count)| (T::from(stone), D::from(count)))
+        .collect(),
+    }
+  }
+
+  pub fn blink(&self, rules: &impl StoneRules<S>) -> Option<Self> {
+    let mut counter: HashMap<S, C> = HashMap::default();
+    for (stone, count) in &self.counter {
+      for next in rules.blink(stone)? {
+        let total = counter.entry(next).or_insert_with(C::zero);
+        *total = total.checked_add(count)?;
+      }
+    }
+    Some(Self { counter })
+  }
+
+  pub fn blinks(self, rules: &impl StoneRules<S>, blinks: usize) -> Option<Self> {
+    (0..blinks).try_fold(self, |stones, _| stones.blink(rules))
+  }
+
+  pub fn stones(&self) -> impl Iterator<Item = &S> + '_ {
+    self.counter.keys()
+  }
+
+  pub fn len(&self) -> Option<C> {
+    self
+      .counter
+      .values()
+      .try_fold(C::zero(), |total, count| total.checked_add(count))
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.counter.is_empty()
+  }
+}
+
+// Count what a single stone grows into, remembering every (stone, blinks) answer
+// so shared descendants are only expanded once
+#[derive(Debug)]
+pub struct StoneCounter<R, S = u64, C = u64> {
+  rules: R,
+  cache: HashMap<(S, usize), C>,
+}
+
+impl<R: StoneRules<S>, S: Stone, C: Count> StoneCounter<R, S, C> {
+  pub fn new(rules: R) -> Self {
+    Self {
+      rules,
+      cache: HashMap::new(),
+    }
+  }
+
+  pub fn count(&mut self, stone: &S, blinks: usize) -> Option<C> {
+    if blinks == 0 {
+      return Some(C::one());
+    }
+    if let Some(count) = self.cache.get(&(stone.clone(), blinks)) {
+      return Some(count.clone());
+    }
+    let count = self
+      .rules
+      .blink(stone)?
+      .iter()
+      .try_fold(C::zero(), |total, next| {
+        total.checked_add(&self.count(next, blinks - 1)?)
+      })?;
+    self.cache.insert((stone.clone(), blinks), count.clone());
+    Some(count)
+  }
+
+  pub fn count_all(&mut self, stones: &MagicStones<S, C>, blinks: usize) -> Option<C> {
+    stones
+      .counter
+      .iter()
+      .try_fold(C::zero(), |total, (stone, count)| {
+        total.checked_add(&count.checked_mul(&self.count(stone, blinks)?)?)
+      })
+  }
+}
+
+// Blink as far as S and C allow. Gives the count once every blink is done, or
+// the last line that still fitted along with how many blinks it has to go
+fn advance<S: Stone, C: Count + Into<BigUint>>(
+  mut stones: MagicStones<S, C>,
+  rules: &impl StoneRules<S>,
+  mut blinks: usize,
+) -> Result<BigUint, (MagicStones<S, C>, usize)> {
+  while blinks > 0 {
+    match stones.blink(rules) {
+      Some(next) => stones = next,
+      None => return Err((stones, blinks)),
+    }
+    blinks -= 1;
+  }
+  stones.len().map(Into::into).ok_or((stones, 0))
+}
+
+// Count the stones after any number of blinks, starting out with machine words and
+// moving up to u128 and then arbitrary precision whenever the stones or the count
+// stop fitting, carrying on from the last line that fitted. Err if the input
+// isn't a line of stones, or the rules themselves give up on a stone
+pub fn count_exact<R>(rules: &R, input: &str, blinks: usize) -> miette::Result<BigUint>
+where
+  R: StoneRules<u64> + StoneRules<u128> + StoneRules<BigUint>,
+{
+  let from_u128 = |stones: MagicStones<u128, BigUint>, blinks| {
+    advance(stones, rules, blinks)
+      .or_else(|(stones, blinks)| advance(stones.widen::<BigUint, BigUint>(), rules, blinks))
+      .ok()
+  };
+  let from_u64 = |stones: MagicStones<u64, u64>, blinks| {
+    advance(stones, rules, blinks)
+      .or_else(|(stones, blinks)| advance(stones.widen::<u64, u128>(), rules, blinks))
+      .or_else(|(stones, blinks)| advance(stones.widen::<u64, BigUint>(), rules, blinks))
+      .map_or_else(|(stones, blinks)| from_u128(stones.widen(), blinks), Some)
+  };
+  // Stones too big to read as a u64 start further up
+  let count = if let Ok(stones) = MagicStones::parse(input) {
+    from_u64(stones, blinks)
+  } else if let Ok(stones) = MagicStones::parse(input) {
+    from_u128(stones, blinks)
+  } else {
+    advance(
+      MagicStones::<BigUint, BigUint>::parse(input)?,
+      rules,
+      blinks,
+    )
+    .ok()
+  };
+  count.ok_or_else(|| miette!("The rules gave up on a stone"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // A made up rule set: every stone splits in two, so the line doubles each blink
+  struct Mitosis;
+
+  impl StoneRules<u64> for Mitosis {
+    fn blink(&self, stone: &u64) -> Option<Vec<u64>> {
+      Some(vec![*stone, *stone + 1])
+    }
+  }
+
+  #[test]
+  fn test_blink() {
+    let stones: MagicStones = MagicStones::parse("0 1 10 99 999").unwrap();
+    assert_eq!(
+      Some(MagicStones::parse("1 2024 1 0 9 9 2021976").unwrap()),
+      stones.blink(&Plutonian)
+    );
+  }
+
+  #[test]
+  fn test_counter_matches_frequency_map() {
+    let stones: MagicStones = MagicStones::parse("125 17").unwrap();
+    let mut counter = StoneCounter::new(Plutonian);
+    for blinks in [0, 1, 6, 25, 40] {
+      assert_eq!(
+        stones
+          .clone()
+          .blinks(&Plutonian, blinks)
+          .and_then(|s| s.len()),
+        counter.count_all(&stones, blinks)
+      );
+    }
+  }
+
+  #[test]
+  fn test_custom_rules() {
+    let stones: MagicStones = MagicStones::parse("3 3").unwrap();
+    assert_eq!(
+      Some(2 << 10),
+      stones.clone().blinks(&Mitosis, 10).and_then(|s| s.len())
+    );
+    assert_eq!(
+      Some(2 << 10),
+      StoneCounter::new(Mitosis).count_all(&stones, 10)
+    );
+    // 2 << 63 no longer fits
+    assert_eq!(None, stones.blinks(&Mitosis, 63).and_then(|s| s.len()));
+  }
+
+  #[test]
+  fn test_not_a_stone() {
+    for input in ["125 x 17", "125 17abc", "12a5 17", "-1", "+1"] {
+      assert!(MagicStones::<u64, u64>::parse(input).is_err(), "{input}");
+      assert!(count_exact(&Plutonian, input, 1).is_err(), "{input}");
+    }
+  }
+
+  #[test]
+  fn test_stone_overflow() {
+    // 19 digits, so it gets multiplied rather than split
+    let stone = 1_000_000_000_000_000_000u64;
+    assert_eq!(None, StoneRules::<u64>::blink(&Plutonian, &stone));
+    assert_eq!(
+      Some(vec![stone as u128 * 2024]),
+      StoneRules::<u128>::blink(&Plutonian, &(stone as u128))
+    );
+    assert!(MagicStones::<u64, u64>::parse("18446744073709551616").is_err());
+    assert_eq!(
+      BigUint::from(2u32),
+      count_exact(&Plutonian, "18446744073709551616", 1).unwrap()
+    );
+  }
+
+  #[test]
+  fn test_count_overflow_boundary() {
+    // Find the first blink where the count no longer fits in a u64
+    let stones = MagicStones::<u64, u128>::parse("125 17").unwrap();
+    let (boundary, _) = (0..)
+      .scan(stones, |stones, blinks| {
+        let count = stones.len()?;
+        *stones = stones.blink(&Plutonian)?;
+        Some((blinks, count))
+      })
+      .find(|&(_, count)| count > u64::MAX as u128)
+      .unwrap();
+
+    let stones = MagicStones::parse("125 17").unwrap();
+    let mut counter = StoneCounter::<_, u64, u64>::new(Plutonian);
+    assert!(counter.count_all(&stones, boundary - 1).is_some());
+    assert_eq!(None, counter.count_all(&stones, boundary));
+
+    let exact = count_exact(&Plutonian, "125 17", boundary).unwrap();
+    assert!(exact > BigUint::from(u64::MAX));
+    let mut wide = StoneCounter::<_, u64, u128>::new(Plutonian);
+    assert_eq!(
+      exact,
+      wide
+        .count_all(&MagicStones::parse("125 17").unwrap(), boundary)
+        .unwrap()
+        .into()
+    );
+  }
+
+  #[test]
+  fn test_deep_blinks() {
+    let exact = count_exact(&Plutonian, "125 17", 300).unwrap();
+    assert!(exact > BigUint::from(u128::MAX));
+    let mut counter = StoneCounter::<_, u64, BigUint>::new(Plutonian);
+    assert_eq!(
+      Some(exact),
+      counter.count_all(&MagicStones::parse("125 17").unwrap(), 300)
+    );
+  }
+}
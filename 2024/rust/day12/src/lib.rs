@@ -1,3 +1,7 @@
 pub mod part1;
 pub mod part1pg;
 pub mod part2;
+pub mod regions;
+
+#[cfg(test)]
+generators::fuzz_tests!(12);
@@ -60,7 +60,8 @@ impl Garden {
         // We have not seen this location before
         for direction in COMPASS {
           let neighbour = (current_pos.0 + direction.0, current_pos.1 + direction.1);
-          if vegetable == self.get_vegetable(&neighbour) {
+          // Only plots on the map, or a '.' plot would spread forever
+          if self.plots.get(&neighbour) == Some(&vegetable) {
             self.remove(&neighbour);
             plot_positions.push(neighbour);
           }
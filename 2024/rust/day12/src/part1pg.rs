@@ -67,6 +67,7 @@ pub fn process(input: &str) -> miette::Result<usize> {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use proptest::{prelude::*, sample::select, test_runner::RngSeed};
 
   #[test]
   fn test_process() -> miette::Result<()> {
@@ -83,4 +84,35 @@ MMMISSJEEE";
     assert_eq!(1930, process(input)?);
     Ok(())
   }
+
+  // Small gardens of a few plants, rows all the same length
+  fn garden() -> impl Strategy<Value = String> {
+    (1..8usize, 1..8usize)
+      .prop_flat_map(|(rows, cols)| {
+        prop::collection::vec(
+          prop::collection::vec(select(vec!['A', 'B', 'C']), cols),
+          rows,
+        )
+      })
+      .prop_map(|rows| {
+        rows
+          .iter()
+          .map(|row| row.iter().collect::<String>())
+          .collect::<Vec<_>>()
+          .join("\n")
+      })
+  }
+
+  proptest! {
+    #![proptest_config(ProptestConfig {
+      rng_seed: RngSeed::Fixed(12),
+      ..ProptestConfig::default()
+    })]
+
+    // The graph condensation and part1 find the same regions
+    #[test]
+    fn test_agrees_with_part1(garden in garden()) {
+      prop_assert_eq!(crate::part1::process(&garden).unwrap(), process(&garden).unwrap());
+    }
+  }
 }
@@ -63,7 +63,8 @@ impl Garden {
         // We have not seen this location before
         for direction in COMPASS {
           let neighbour = (current_pos.0 + direction.0, current_pos.1 + direction.1);
-          if vegetable == self.get_vegetable(&neighbour).unwrap_or('.') {
+          // Only plots on the map, or a '.' plot would spread forever
+          if self.get_vegetable(&neighbour) == Some(vegetable) {
             self.remove(&neighbour);
             plot_positions.push(neighbour);
           }
@@ -75,7 +76,7 @@ impl Garden {
 
     let sides = seen
       .iter()
-      .map(|plot_spot| self.count_corners(&plot_spot, &vegetable))
+      .map(|plot_spot| self.count_corners(plot_spot, &vegetable))
       .sum();
 
     (area, sides)
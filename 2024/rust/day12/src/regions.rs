@@ -0,0 +1,468 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+
+pub type Position = (i32, i32);
+
+// Which side of a plot a fence runs along
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+  North,
+  East,
+  South,
+  West,
+}
+
+impl Side {
+  const ALL: [Side; 4] = [Side::North, Side::East, Side::South, Side::West];
+
+  // Step from a plot across this side to its neighbour
+  pub fn offset(&self) -> Position {
+    match self {
+      Side::North => (-1, 0),
+      Side::East => (0, 1),
+      Side::South => (1, 0),
+      Side::West => (0, -1),
+    }
+  }
+
+  // Direction a straight run of fence on this side follows: north and south
+  // fences run left to right, east and west fences run top to bottom
+  fn run(&self) -> Position {
+    match self {
+      Side::North | Side::South => (0, 1),
+      Side::East | Side::West => (1, 0),
+    }
+  }
+}
+
+// A straight run of fence along one side of `length` plots, starting at the
+// top-most or left-most plot it borders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment {
+  pub side: Side,
+  pub start: Position,
+  pub length: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+  pub plant: char,
+  pub plots: Vec<Position>,
+  pub area: usize,
+  pub perimeter: usize,
+  // Every straight run of fence is one side
+  pub sides: usize,
+  pub segments: Vec<Segment>,
+}
+
+// Inclusive corners of the smallest rectangle holding every plot in a region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox {
+  pub top_left: Position,
+  pub bottom_right: Position,
+}
+
+impl BoundingBox {
+  pub fn contains(&self, (row, col): Position) -> bool {
+    (self.top_left.0..=self.bottom_right.0).contains(&row)
+      && (self.top_left.1..=self.bottom_right.1).contains(&col)
+  }
+}
+
+impl Region {
+  pub fn bounding_box(&self) -> BoundingBox {
+    let rows = self.plots.iter().map(|plot| plot.0);
+    let cols = self.plots.iter().map(|plot| plot.1);
+    BoundingBox {
+      top_left: (
+        rows.clone().min().unwrap_or_default(),
+        cols.clone().min().unwrap_or_default(),
+      ),
+      bottom_right: (
+        rows.max().unwrap_or_default(),
+        cols.max().unwrap_or_default(),
+      ),
+    }
+  }
+}
+
+// What a region closes off from the rest of the garden
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Enclosure {
+  pub bounding_box: BoundingBox,
+  // Each hole is a patch of plots that can't get out of the garden without
+  // crossing this region
+  pub holes: Vec<Vec<Position>>,
+  // Labels of the regions sitting entirely inside the holes
+  pub contains: Vec<usize>,
+}
+
+impl Enclosure {
+  pub fn has_holes(&self) -> bool {
+    !self.holes.is_empty()
+  }
+}
+
+#[derive(Debug)]
+pub struct Garden {
+  plants: Vec<Vec<char>>,
+  // Index into `regions` for every plot
+  labels: Vec<Vec<usize>>,
+  pub regions: Vec<Region>,
+}
+
+impl From<&str> for Garden {
+  fn from(input: &str) -> Self {
+    let plants: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+    let mut labels: Vec<Vec<usize>> = plants
+      .iter()
+      .map(|row| vec![usize::MAX; row.len()])
+      .collect();
+    let mut regions = vec![];
+
+    for (row, line) in plants.iter().enumerate() {
+      for (col, &plant) in line.iter().enumerate() {
+        if labels[row][col] != usize::MAX {
+          continue;
+        }
+        // Flood fill everything connected to this plot with the same plant
+        let label = regions.len();
+        let mut plots = vec![];
+        let mut stack = vec![(row as i32, col as i32)];
+        labels[row][col] = label;
+        while let Some(plot) = stack.pop() {
+          plots.push(plot);
+          for side in Side::ALL {
+            let (row, col) = (plot.0 + side.offset().0, plot.1 + side.offset().1);
+            if row < 0 || col < 0 {
+              continue;
+            }
+            let (row, col) = (row as usize, col as usize);
+            if plants.get(row).and_then(|line| line.get(col)) == Some(&plant)
+              && labels[row][col] == usize::MAX
+            {
+              labels[row][col] = label;
+              stack.push((row as i32, col as i32));
+            }
+          }
+        }
+        plots.sort_unstable();
+        regions.push(Region {
+          plant,
+          area: plots.len(),
+          plots,
+          perimeter: 0,
+          sides: 0,
+          segments: vec![],
+        });
+      }
+    }
+
+    let mut garden = Self {
+      plants,
+      labels,
+      regions,
+    };
+    for label in 0..garden.regions.len() {
+      let segments = garden.segments(label);
+      let region = &mut garden.regions[label];
+      region.perimeter = segments.iter().map(|segment| segment.length).sum();
+      region.sides = segments.len();
+      region.segments = segments;
+    }
+    garden
+  }
+}
+
+impl Garden {
+  pub fn label(&self, (row, col): Position) -> Option<usize> {
+    if row < 0 || col < 0 {
+      return None;
+    }
+    self.labels.get(row as usize)?.get(col as usize).copied()
+  }
+
+  pub fn plant(&self, (row, col): Position) -> Option<char> {
+    if row < 0 || col < 0 {
+      return None;
+    }
+    self.plants.get(row as usize)?.get(col as usize).copied()
+  }
+
+  pub fn region_at(&self, position: Position) -> Option<&Region> {
+    self.regions.get(self.label(position)?)
+  }
+
+  fn fenced(&self, label: usize, plot: Position, side: Side) -> bool {
+    let (row, col) = side.offset();
+    self.label(plot) == Some(label) && self.label((plot.0 + row, plot.1 + col)) != Some(label)
+  }
+
+  // Join up the fence along each side of each plot into straight runs
+  fn segments(&self, label: usize) -> Vec<Segment> {
+    let mut segments = vec![];
+    for &plot in &self.regions[label].plots {
+      for side in Side::ALL {
+        let (run_row, run_col) = side.run();
+        let before = (plot.0 - run_row, plot.1 - run_col);
+        // Only start counting from the first plot in the run
+        if !self.fenced(label, plot, side) || self.fenced(label, before, side) {
+          continue;
+        }
+        let length = (0..)
+          .take_while(|&step| {
+            self.fenced(
+              label,
+              (plot.0 + run_row * step, plot.1 + run_col * step),
+              side,
+            )
+          })
+          .count();
+        segments.push(Segment {
+          side,
+          start: plot,
+          length,
+        });
+      }
+    }
+    segments
+  }
+
+  // Flood in from just outside the region's bounding box: anything the water
+  // can't reach without crossing the region is in one of its holes
+  pub fn enclosure(&self, label: usize) -> Enclosure {
+    let bounding_box = self.regions[label].bounding_box();
+    let margin = BoundingBox {
+      top_left: (bounding_box.top_left.0 - 1, bounding_box.top_left.1 - 1),
+      bottom_right: (
+        bounding_box.bottom_right.0 + 1,
+        bounding_box.bottom_right.1 + 1,
+      ),
+    };
+    let open = |plot: Position| margin.contains(plot) && self.label(plot) != Some(label);
+
+    let mut outside = HashSet::new();
+    let mut stack = vec![margin.top_left];
+    outside.insert(margin.top_left);
+    while let Some(plot) = stack.pop() {
+      for side in Side::ALL {
+        let next = (plot.0 + side.offset().0, plot.1 + side.offset().1);
+        if open(next) && outside.insert(next) {
+          stack.push(next);
+        }
+      }
+    }
+
+    let mut holes: Vec<Vec<Position>> = vec![];
+    let mut seen = HashSet::new();
+    for row in bounding_box.top_left.0..=bounding_box.bottom_right.0 {
+      for col in bounding_box.top_left.1..=bounding_box.bottom_right.1 {
+        let start = (row, col);
+        if !open(start) || outside.contains(&start) || !seen.insert(start) {
+          continue;
+        }
+        let mut hole = vec![];
+        let mut stack = vec![start];
+        while let Some(plot) = stack.pop() {
+          hole.push(plot);
+          for side in Side::ALL {
+            let next = (plot.0 + side.offset().0, plot.1 + side.offset().1);
+            if open(next) && seen.insert(next) {
+              stack.push(next);
+            }
+          }
+        }
+        hole.sort_unstable();
+        holes.push(hole);
+      }
+    }
+
+    // Regions are connected and never straddle the enclosing region,
+    // so one plot in a hole puts the whole region in it
+    let contains = holes
+      .iter()
+      .flatten()
+      .filter_map(|&plot| self.label(plot))
+      .sorted()
+      .dedup()
+      .collect();
+
+    Enclosure {
+      bounding_box,
+      holes,
+      contains,
+    }
+  }
+
+  // Draw a single region at double resolution, so the fence gets its own rows and
+  // columns between the plots. Plots outside the region show as '.'
+  pub fn render(&self, label: usize) -> String {
+    let BoundingBox {
+      top_left: (top, left),
+      bottom_right: (bottom, right),
+    } = self.regions[label].bounding_box();
+    let height = 2 * (bottom - top + 1) + 1;
+    let width = 2 * (right - left + 1) + 1;
+    // Map a point on the drawing back to the plot above-left of it
+    let plot = |y: i32, x: i32| (top + y.div_euclid(2), left + x.div_euclid(2));
+    let fence_on = |y: i32, x: i32| -> bool {
+      if y < 0 || x < 0 || y >= height || x >= width {
+        return false;
+      }
+      match (y % 2, x % 2) {
+        // Between two plots stacked vertically
+        (0, 1) => {
+          self.fenced(label, plot(y - 1, x), Side::South)
+            || self.fenced(label, plot(y + 1, x), Side::North)
+        }
+        // Between two plots side by side
+        (1, 0) => {
+          self.fenced(label, plot(y, x - 1), Side::East)
+            || self.fenced(label, plot(y, x + 1), Side::West)
+        }
+        _ => false,
+      }
+    };
+
+    (0..height)
+      .map(|y| {
+        (0..width)
+          .map(|x| match (y % 2, x % 2) {
+            (1, 1) if self.label(plot(y, x)) == Some(label) => self.regions[label].plant,
+            (1, 1) => '.',
+            (0, 1) if fence_on(y, x) => '-',
+            (1, 0) if fence_on(y, x) => '|',
+            (0, 0) => {
+              let across = fence_on(y, x - 1) || fence_on(y, x + 1);
+              let down = fence_on(y - 1, x) || fence_on(y + 1, x);
+              match (across, down) {
+                (true, true) => '+',
+                (true, false) => '-',
+                (false, true) => '|',
+                (false, false) => ' ',
+              }
+            }
+            _ => ' ',
+          })
+          .collect::<String>()
+      })
+      .join("\n")
+  }
+}
+
+// Part 1 pricing: area times perimeter
+pub fn price(regions: &[Region]) -> usize {
+  regions
+    .iter()
+    .map(|region| region.area * region.perimeter)
+    .sum()
+}
+
+// Part 2 bulk discount pricing: area times number of sides
+pub fn bulk_price(regions: &[Region]) -> usize {
+  regions
+    .iter()
+    .map(|region| region.area * region.sides)
+    .sum()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{part1, part1pg, part2};
+  use rstest::rstest;
+
+  #[rstest]
+  #[case("AAAA\nBBCD\nBBCC\nEEEC", 140, 80)]
+  #[case("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO", 772, 436)]
+  #[case("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE", 692, 236)]
+  #[case("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA", 1184, 368)]
+  #[case(
+    "RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE",
+    1930,
+    1206
+  )]
+  fn test_pricing_agrees(
+    #[case] input: &str,
+    #[case] expected: usize,
+    #[case] bulk: usize,
+  ) -> miette::Result<()> {
+    let garden = Garden::from(input);
+    assert_eq!(expected, price(&garden.regions));
+    assert_eq!(expected, part1::process(input)?);
+    assert_eq!(expected, part1pg::process(input)?);
+    assert_eq!(bulk, bulk_price(&garden.regions));
+    assert_eq!(bulk, part2::process(input)?);
+    Ok(())
+  }
+
+  #[test]
+  fn test_region() {
+    let garden = Garden::from("AAAA\nBBCD\nBBCC\nEEEC");
+    let c = garden.region_at((2, 3)).unwrap();
+    assert_eq!('C', c.plant);
+    assert_eq!(vec![(1, 2), (2, 2), (2, 3), (3, 3)], c.plots);
+    assert_eq!(4, c.area);
+    assert_eq!(10, c.perimeter);
+    assert_eq!(8, c.sides);
+    assert!(c.segments.contains(&Segment {
+      side: Side::North,
+      start: (2, 3),
+      length: 1
+    }));
+    assert!(c.segments.contains(&Segment {
+      side: Side::East,
+      start: (2, 3),
+      length: 2
+    }));
+    assert_eq!(5, garden.regions.len());
+  }
+
+  #[test]
+  fn test_enclosure() {
+    let garden = Garden::from("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO");
+    let outer = garden.label((0, 0)).unwrap();
+    let enclosure = garden.enclosure(outer);
+    assert!(enclosure.has_holes());
+    assert_eq!(4, enclosure.holes.len());
+    assert_eq!(4, enclosure.contains.len());
+    assert_eq!(
+      BoundingBox {
+        top_left: (0, 0),
+        bottom_right: (4, 4)
+      },
+      enclosure.bounding_box
+    );
+    let inner = garden.label((1, 1)).unwrap();
+    assert!(!garden.enclosure(inner).has_holes());
+
+    // B touches the edge of the garden, so A doesn't enclose it
+    let garden = Garden::from("AAB\nABA\nAAA");
+    let enclosure = garden.enclosure(garden.label((0, 0)).unwrap());
+    assert_eq!(vec![vec![(1, 1)]], enclosure.holes);
+    assert_eq!(vec![garden.label((1, 1)).unwrap()], enclosure.contains);
+  }
+
+  #[test]
+  fn test_render() {
+    let garden = Garden::from("AAAA\nBBCD\nBBCC\nEEEC");
+    let expected = "+-+  
+|C|. 
+| +-+
+|C C|
++-+ |
+ .|C|
+  +-+";
+    assert_eq!(expected, garden.render(garden.label((1, 2)).unwrap()));
+  }
+}
@@ -1,4 +1,8 @@
 pub mod parsers;
 pub mod part1;
 pub mod part2;
+pub mod solver;
 pub mod types;
+
+#[cfg(test)]
+generators::fuzz_tests!(13);
@@ -1,25 +1,29 @@
 use nom::{
   bytes::complete::tag,
-  character::complete::{self, line_ending},
-  multi::separated_list1,
-  sequence::{preceded, separated_pair, terminated, tuple},
+  character::complete::{self, line_ending, satisfy},
+  combinator::opt,
+  multi::{many1, separated_list1},
+  sequence::{delimited, preceded, separated_pair, terminated, tuple},
   IResult, Parser,
 };
 
 use crate::types::{Button, ClawMachine, Prize};
 
-fn a_button(input: &str) -> IResult<&str, Button> {
-  preceded(
-    tag("Button A: X+"),
-    separated_pair(complete::i64, tag(", Y+"), complete::i64).map(|(x, y)| Button::new(x, y)),
-  )(input)
-}
-
-fn b_button(input: &str) -> IResult<&str, Button> {
-  preceded(
-    tag("Button B: X+"),
-    separated_pair(complete::i64, tag(", Y+"), complete::i64).map(|(x, y)| Button::new(x, y)),
-  )(input)
+// Button A: X+94, Y+34
+// with an optional price on the end, e.g. Button C: X+5, Y-2, Cost=7
+fn button(input: &str) -> IResult<&str, Button> {
+  let (input, label) = delimited(
+    tag("Button "),
+    satisfy(|c| c.is_ascii_alphanumeric()),
+    tag(": X"),
+  )(input)?;
+  let (input, (x, y)) = separated_pair(complete::i64, tag(", Y"), complete::i64)(input)?;
+  let (input, cost) = opt(preceded(tag(", Cost="), complete::i64))(input)?;
+  let mut button = Button::new(label, x, y);
+  if let Some(cost) = cost {
+    button.cost = cost;
+  }
+  Ok((input, button))
 }
 
 fn prize(input: &str) -> IResult<&str, Prize> {
@@ -30,15 +34,41 @@ fn prize(input: &str) -> IResult<&str, Prize> {
 }
 
 fn machine(input: &str) -> IResult<&str, ClawMachine> {
-  let (input, (a, b, prize)) = tuple((
-    terminated(a_button, line_ending),
-    terminated(b_button, line_ending),
-    prize,
-  ))(input)?;
-  Ok((input, ClawMachine { a, b, prize }))
+  let (input, (buttons, prize)) = tuple((many1(terminated(button, line_ending)), prize))(input)?;
+  Ok((input, ClawMachine { buttons, prize }))
 }
 
 pub fn parse(input: &str) -> IResult<&str, Vec<ClawMachine>> {
   let result = separated_list1(tuple((line_ending, line_ending)), machine)(input)?;
   Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_buttons() {
+    let (_, machines) = parse(
+      "Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+1, Y+1
+Button B: X+2, Y-1, Cost=4
+Button C: X-3, Y+5
+Prize: X=10, Y=10",
+    )
+    .unwrap();
+    assert_eq!(2, machines.len());
+    assert_eq!(
+      vec![Button::new('A', 94, 34), Button::new('B', 22, 67)],
+      machines[0].buttons
+    );
+    let b = machines[1].button('B').unwrap();
+    assert_eq!((2, -1, 4), (b.step.x, b.step.y, b.cost));
+    assert_eq!(1, machines[1].button('C').unwrap().cost);
+    assert_eq!(3, machines[1].button('A').unwrap().cost);
+    assert_eq!(None, machines[1].button('D'));
+  }
+}
@@ -1,39 +1,29 @@
-use glam::I64Vec2;
 use miette::miette;
-use pathfinding::prelude::dijkstra;
 
 use crate::parsers::parse;
+use crate::solver::{solve, Options};
 
-const A_COST: u32 = 3;
-const B_COST: u32 = 1;
+// No button is pressed more than 100 times in part 1
+const PRESS_LIMIT: i64 = 100;
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-  let (_, machines) = parse(input).map_err(|e| miette!("parse failed {}", e))?;
-  let sum: u32 = machines
+  let (remaining_input, machines) = parse(input).map_err(|e| miette!("parse failed {}", e))?;
+  if !remaining_input.trim().is_empty() {
+    return Err(miette!(
+      "unexpected input after the machines {remaining_input:?}"
+    ));
+  }
+  let options = Options {
+    press_limit: Some(PRESS_LIMIT),
+    ..Options::default()
+  };
+  machines
     .iter()
-    .map(|machine| {
-      let start_node = I64Vec2::ZERO;
-      let result = dijkstra(
-        &start_node,
-        |position| {
-          if position.x > machine.prize.x || position.y > machine.prize.y {
-            vec![]
-          } else {
-            vec![
-              (position + machine.a, A_COST),
-              (position + machine.b, B_COST),
-            ]
-          }
-        },
-        |&goal| goal == machine.prize,
-      );
-      result.map(|(_path, cost)| cost)
-    })
-    .flatten()
-    .sum();
-
-  Ok(sum.to_string())
+    .filter_map(|machine| solve(machine, &options))
+    .try_fold(0i128, |sum, presses| sum.checked_add(presses.tokens))
+    .map(|sum| sum.to_string())
+    .ok_or_else(|| miette!("token total doesn't fit in an i128"))
 }
 
 #[cfg(test)]
@@ -0,0 +1,600 @@
+use crate::types::{Button, ClawMachine};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Options {
+  // Added to both X and Y of every prize
+  pub prize_offset: i64,
+  // Most times any one button may be pressed
+  pub press_limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Presses {
+  // How often each button is pressed, in the machine's button order
+  pub counts: Vec<i128>,
+  pub tokens: i128,
+}
+
+type Vector = (i128, i128);
+
+fn step(button: &Button) -> Vector {
+  (button.step.x as i128, button.step.y as i128)
+}
+
+fn along(button: &Button, axis: usize) -> i128 {
+  let (x, y) = step(button);
+  [x, y][axis]
+}
+
+// Returns (g, x, y) with a * x + b * y = g = gcd(a, b), g >= 0
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+  if b == 0 {
+    (a.abs(), a.signum(), 0)
+  } else {
+    let (g, x, y) = extended_gcd(b, a.rem_euclid(b));
+    (g, y, x - a.div_euclid(b) * y)
+  }
+}
+
+fn div_floor(a: i128, b: i128) -> i128 {
+  let quotient = a / b;
+  if (a % b != 0) && ((a < 0) != (b < 0)) {
+    quotient - 1
+  } else {
+    quotient
+  }
+}
+
+fn div_ceil(a: i128, b: i128) -> i128 {
+  -div_floor(-a, b)
+}
+
+// Cheapest (m, n) within [0, limit] with a * m + b * n = p, when there is one
+fn cheapest_on_line(
+  (a, a_cost): (i128, i128),
+  (b, b_cost): (i128, i128),
+  p: i128,
+  limit: Option<i128>,
+) -> Option<(i128, i128)> {
+  if a == 0 && b == 0 {
+    return (p == 0).then_some((0, 0));
+  }
+  let (g, x, y) = extended_gcd(a, b);
+  if p % g != 0 {
+    return None;
+  }
+  // Every solution is (m0 + k * step_m, n0 - k * step_n) for some integer k
+  let (m0, n0) = (x.checked_mul(p / g)?, y.checked_mul(p / g)?);
+  let (step_m, step_n) = (b / g, a / g);
+
+  // Work out the range of k that keeps both press counts in range,
+  // None at either end meaning no limit that way
+  let mut low: Option<i128> = None;
+  let mut high: Option<i128> = None;
+  let mut bound = |value: i128, step: i128| {
+    // value + k * step >= 0
+    match step.signum() {
+      1 => {
+        low = Some(low.map_or(div_ceil(-value, step), |low| {
+          low.max(div_ceil(-value, step))
+        }))
+      }
+      -1 => {
+        high = Some(high.map_or(div_floor(value, -step), |high| {
+          high.min(div_floor(value, -step))
+        }))
+      }
+      _ => (),
+    }
+    step != 0 || value >= 0
+  };
+  if !bound(m0, step_m) || !bound(n0, -step_n) {
+    return None;
+  }
+  if let Some(limit) = limit {
+    if !bound(limit.checked_sub(m0)?, -step_m) || !bound(limit.checked_sub(n0)?, step_n) {
+      return None;
+    }
+  }
+
+  // Tokens change linearly with k, so the cheapest end of the range wins
+  let slope = a_cost
+    .checked_mul(step_m)?
+    .checked_sub(b_cost.checked_mul(step_n)?)?;
+  let k = match (slope.signum(), low, high) {
+    (_, Some(low), Some(high)) if low > high => return None,
+    (1, Some(low), _) => low,
+    (-1, _, Some(high)) => high,
+    (0, Some(low), _) => low,
+    (0, None, Some(high)) => high,
+    (0, None, None) => 0,
+    // Cheaper and cheaper forever - only possible with negative costs
+    _ => return None,
+  };
+  Some((
+    m0.checked_add(k.checked_mul(step_m)?)?,
+    n0.checked_sub(k.checked_mul(step_n)?)?,
+  ))
+}
+
+// u * v - w * z
+fn cross(u: i128, v: i128, w: i128, z: i128) -> Option<i128> {
+  u.checked_mul(v)?.checked_sub(w.checked_mul(z)?)
+}
+
+fn in_range(presses: i128, limit: Option<i128>) -> bool {
+  presses >= 0 && limit.is_none_or(|limit| presses <= limit)
+}
+
+// The cheapest way to reach the prize with exactly two buttons
+fn pair(a: &Button, b: &Button, (px, py): Vector, limit: Option<i128>) -> Option<(i128, i128)> {
+  /*
+   * Thanks to @UncleScientist for this solution
+   *
+   * m = number of times button_a is pushed
+   * n = number of times button_b is pushed
+   *
+   * p.x = a.x * m + b.x * n
+   * p.y = a.y * m + b.y * n
+   *
+   * Which solves (Cramer's rule) to
+   *
+   *      a.x * p.y - a.y * p.x          p.x * b.y - p.y * b.x
+   * n =  ---------------------     m =  ---------------------
+   *      a.x * b.y - a.y * b.x          a.x * b.y - a.y * b.x
+   *
+   * Everything is done in i128, and checked, so nothing can silently wrap
+   */
+  let ((ax, ay), (bx, by)) = (step(a), step(b));
+  let (a_cost, b_cost) = (a.cost as i128, b.cost as i128);
+
+  let determinant = cross(ax, by, ay, bx)?;
+  let (m, n) = if determinant != 0 {
+    let m_numerator = cross(px, by, py, bx)?;
+    let n_numerator = cross(ax, py, ay, px)?;
+    if m_numerator % determinant != 0 || n_numerator % determinant != 0 {
+      return None;
+    }
+    (m_numerator / determinant, n_numerator / determinant)
+  } else {
+    // The buttons push the claw along the same line, so the prize has to be on
+    // it too. Then one equation says everything, and there may be many answers
+    if cross(ax, py, ay, px)? != 0 || cross(bx, py, by, px)? != 0 {
+      return None;
+    }
+    if ax != 0 || bx != 0 {
+      cheapest_on_line((ax, a_cost), (bx, b_cost), px, limit)?
+    } else {
+      cheapest_on_line((ay, a_cost), (by, b_cost), py, limit)?
+    }
+  };
+
+  let reach = |u: i128, w: i128| u.checked_mul(m)?.checked_add(w.checked_mul(n)?);
+  (in_range(m, limit) && in_range(n, limit) && reach(ax, bx)? == px && reach(ay, by)? == py)
+    .then_some((m, n))
+}
+
+// Presses of a single button that land exactly on the prize
+fn single(button: &Button, (px, py): Vector, limit: Option<i128>) -> Option<i128> {
+  let (sx, sy) = step(button);
+  let presses = match (sx, sy) {
+    (0, 0) => 0,
+    (0, _) => py / sy,
+    _ => px / sx,
+  };
+  (in_range(presses, limit) && sx.checked_mul(presses)? == px && sy.checked_mul(presses)? == py)
+    .then_some(presses)
+}
+
+// Branch and bound over every button but the last two, which are then solved exactly
+struct Search<'a> {
+  buttons: &'a [Button],
+  limit: Option<i128>,
+  counts: Vec<i128>,
+  best: Option<Presses>,
+  // Set if some button could have been pressed any number of times
+  unbounded: bool,
+}
+
+impl Search<'_> {
+  fn best_tokens(&self) -> Option<i128> {
+    self.best.as_ref().map(|best| best.tokens)
+  }
+
+  // No answer using these buttons can cost less than this, None if there's
+  // provably no answer at all. Only looks along axes nothing moves backwards on
+  fn lower_bound(buttons: &[Button], (rx, ry): Vector) -> Option<i128> {
+    let mut bound = 0;
+    for (axis, remaining) in [rx, ry].into_iter().enumerate() {
+      if buttons.iter().any(|button| along(button, axis) < 0) {
+        continue;
+      }
+      if remaining < 0 {
+        return None;
+      }
+      if remaining == 0 || buttons.iter().any(|button| button.cost < 0) {
+        continue;
+      }
+      // The fewest tokens if presses could be fractional
+      let cheapest = buttons
+        .iter()
+        .filter(|button| along(button, axis) > 0)
+        .filter_map(|button| {
+          Some(div_ceil(
+            remaining.checked_mul(button.cost as i128)?,
+            along(button, axis),
+          ))
+        })
+        .min()?;
+      bound = bound.max(cheapest);
+    }
+    Some(bound)
+  }
+
+  // The most times the button at index could usefully be pressed
+  fn upper_bound(&self, index: usize, (rx, ry): Vector, spent: i128) -> Option<i128> {
+    let button = &self.buttons[index];
+    let mut bound = self.limit;
+    let mut tighten = |most: i128| bound = Some(bound.map_or(most, |bound| bound.min(most)));
+    for (axis, remaining) in [rx, ry].into_iter().enumerate() {
+      let moves_back = self.buttons[index..]
+        .iter()
+        .any(|button| along(button, axis) < 0);
+      if along(button, axis) > 0 && !moves_back {
+        tighten(div_floor(remaining, along(button, axis)).max(-1));
+      }
+    }
+    if let (Some(best), true) = (self.best_tokens(), button.cost > 0) {
+      tighten(div_floor(best - spent, button.cost as i128));
+    }
+    bound
+  }
+
+  fn record(&mut self, tokens: i128) {
+    if self.best_tokens().is_none_or(|best| tokens < best) {
+      self.best = Some(Presses {
+        counts: self.counts.clone(),
+        tokens,
+      });
+    }
+  }
+
+  fn search(&mut self, index: usize, remaining: Vector, spent: i128) {
+    let rest = &self.buttons[index..];
+    if let [a, b] = rest {
+      let tokens = pair(a, b, remaining, self.limit).and_then(|(m, n)| {
+        self.counts[index..].copy_from_slice(&[m, n]);
+        spent
+          .checked_add((a.cost as i128).checked_mul(m)?)?
+          .checked_add((b.cost as i128).checked_mul(n)?)
+      });
+      if let Some(tokens) = tokens {
+        self.record(tokens);
+      }
+      return;
+    }
+
+    let Some(most) = self.upper_bound(index, remaining, spent) else {
+      self.unbounded = true;
+      return;
+    };
+    let button = self.buttons[index];
+    let (sx, sy) = step(&button);
+    for presses in 0..=most {
+      let next = || {
+        let remaining = (
+          remaining.0.checked_sub(sx.checked_mul(presses)?)?,
+          remaining.1.checked_sub(sy.checked_mul(presses)?)?,
+        );
+        let spent = spent.checked_add((button.cost as i128).checked_mul(presses)?)?;
+        Some((remaining, spent))
+      };
+      let Some((remaining, spent)) = next() else {
+        break;
+      };
+      let Some(bound) = Self::lower_bound(&self.buttons[index + 1..], remaining) else {
+        continue;
+      };
+      if self
+        .best_tokens()
+        .is_some_and(|best| spent.saturating_add(bound) >= best)
+      {
+        continue;
+      }
+      self.counts[index] = presses;
+      self.search(index + 1, remaining, spent);
+    }
+  }
+}
+
+// The cheapest presses that win the prize. None if there aren't any, or if the
+// buttons can pull the claw both ways along both axes and there's no press
+// limit to stop the search going on forever
+pub fn solve(machine: &ClawMachine, options: &Options) -> Option<Presses> {
+  let prize = (
+    machine.prize.x as i128 + options.prize_offset as i128,
+    machine.prize.y as i128 + options.prize_offset as i128,
+  );
+  let limit = options.press_limit.map(i128::from);
+  // A button that doesn't move the claw is never worth pressing, unless it
+  // pays out tokens. Left in, it has no bound and would stop the search
+  let useful: Vec<usize> = (0..machine.buttons.len())
+    .filter(|&index| {
+      let button = &machine.buttons[index];
+      step(button) != (0, 0) || button.cost < 0
+    })
+    .collect();
+  let buttons: Vec<Button> = useful.iter().map(|&index| machine.buttons[index]).collect();
+  let counts = match buttons.as_slice() {
+    [] => (prize == (0, 0)).then(Vec::new)?,
+    [button] => vec![single(button, prize, limit)?],
+    [a, b] => {
+      let (m, n) = pair(a, b, prize, limit)?;
+      vec![m, n]
+    }
+    buttons => {
+      let mut search = Search {
+        buttons,
+        limit,
+        counts: vec![0; buttons.len()],
+        best: None,
+        unbounded: false,
+      };
+      search.search(0, prize, 0);
+      if search.unbounded {
+        return None;
+      }
+      search.best?.counts
+    }
+  };
+  let tokens = counts
+    .iter()
+    .zip(&buttons)
+    .try_fold(0i128, |total, (&presses, button)| {
+      total.checked_add((button.cost as i128).checked_mul(presses)?)
+    })?;
+  // Back in the machine's button order, with the idle buttons never pressed
+  let mut all_counts = vec![0; machine.buttons.len()];
+  for (index, presses) in useful.into_iter().zip(counts) {
+    all_counts[index] = presses;
+  }
+  Some(Presses {
+    counts: all_counts,
+    tokens,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{parsers::parse, types::Prize};
+  use rstest::rstest;
+
+  fn machine(buttons: &[(i64, i64)], prize: (i64, i64)) -> ClawMachine {
+    ClawMachine {
+      buttons: buttons
+        .iter()
+        .zip('A'..)
+        .map(|(&(x, y), label)| Button::new(label, x, y))
+        .collect(),
+      prize: Prize::new(prize.0, prize.1),
+    }
+  }
+
+  #[test]
+  fn test_unique_solution() {
+    let presses = solve(
+      &machine(&[(94, 34), (22, 67)], (8400, 5400)),
+      &Options::default(),
+    );
+    assert_eq!(
+      Some(Presses {
+        counts: vec![80, 40],
+        tokens: 280
+      }),
+      presses
+    );
+    assert_eq!(
+      None,
+      solve(
+        &machine(&[(26, 66), (67, 21)], (12748, 12176)),
+        &Options::default()
+      )
+    );
+  }
+
+  #[rstest]
+  // 0 A + 5 B or 2 A + 2 B both work, and B is cheaper per step
+  #[case((3, 3), (2, 2), (10, 10), Some((0, 5, 5)))]
+  // Both buttons the same, so only ever press B
+  #[case((2, 2), (2, 2), (10, 10), Some((0, 5, 5)))]
+  // The prize is off the line
+  #[case((1, 2), (2, 4), (5, 9), None)]
+  // Only one button does anything, and it can't get to an odd prize
+  #[case((0, 0), (2, 2), (5, 5), None)]
+  #[case((0, 0), (2, 2), (6, 6), Some((0, 3, 3)))]
+  // Vertical line, so it all comes down to Y
+  #[case((0, 3), (0, 5), (0, 11), Some((2, 1, 7)))]
+  fn test_collinear_buttons(
+    #[case] a: (i64, i64),
+    #[case] b: (i64, i64),
+    #[case] prize: (i64, i64),
+    #[case] expected: Option<(i128, i128, i128)>,
+  ) {
+    let presses = solve(&machine(&[a, b], prize), &Options::default());
+    assert_eq!(
+      expected,
+      presses.map(|p| (p.counts[0], p.counts[1], p.tokens))
+    );
+  }
+
+  #[test]
+  fn test_costs_and_offset() {
+    let mut collinear = machine(&[(3, 3), (2, 2)], (10, 10));
+    collinear.buttons[0].cost = 1;
+    collinear.buttons[1].cost = 5;
+    // 10 = 3 * 2 + 2 * 2, as few B presses as we can get away with
+    assert_eq!(
+      Some(Presses {
+        counts: vec![2, 2],
+        tokens: 12
+      }),
+      solve(&collinear, &Options::default())
+    );
+    let offset = Options {
+      prize_offset: 10_000_000_000_000,
+      ..Options::default()
+    };
+    assert_eq!(
+      Some(459236326669),
+      solve(&machine(&[(26, 66), (67, 21)], (12748, 12176)), &offset).map(|p| p.tokens)
+    );
+  }
+
+  #[test]
+  fn test_no_overflow() {
+    let huge = machine(&[(i64::MAX, 1), (1, i64::MAX)], (i64::MAX, i64::MAX));
+    let offset = Options {
+      prize_offset: i64::MAX,
+      ..Options::default()
+    };
+    assert_eq!(None, solve(&huge, &offset));
+  }
+
+  #[test]
+  fn test_press_limit() {
+    let limited = Options {
+      press_limit: Some(100),
+      ..Options::default()
+    };
+    let steep = machine(&[(1, 1), (1, 2)], (150, 150));
+    assert_eq!(None, solve(&steep, &limited));
+    assert_eq!(
+      Some(vec![150, 0]),
+      solve(&steep, &Options::default()).map(|p| p.counts)
+    );
+    // The cheap way is 120 presses of B, so make up the rest with A
+    let collinear = machine(&[(2, 2), (1, 1)], (120, 120));
+    assert_eq!(
+      Some(vec![10, 100]),
+      solve(&collinear, &limited).map(|p| p.counts)
+    );
+    assert_eq!(
+      Some(vec![0, 120]),
+      solve(&collinear, &Options::default()).map(|p| p.counts)
+    );
+  }
+
+  #[test]
+  fn test_one_button() {
+    assert_eq!(
+      Some(vec![4]),
+      solve(&machine(&[(2, 3)], (8, 12)), &Options::default()).map(|p| p.counts)
+    );
+    assert_eq!(
+      None,
+      solve(&machine(&[(2, 3)], (8, 13)), &Options::default())
+    );
+  }
+
+  // Try every combination of presses up to the limit
+  fn brute_force(machine: &ClawMachine, limit: i128) -> Option<i128> {
+    let mut best: Option<i128> = None;
+    let mut counts = vec![0i128; machine.buttons.len()];
+    loop {
+      let (mut x, mut y, mut tokens) = (0, 0, 0);
+      for (presses, button) in counts.iter().zip(&machine.buttons) {
+        x += presses * button.step.x as i128;
+        y += presses * button.step.y as i128;
+        tokens += presses * button.cost as i128;
+      }
+      if (x, y) == (machine.prize.x as i128, machine.prize.y as i128) {
+        best = Some(best.map_or(tokens, |best| best.min(tokens)));
+      }
+      let Some(digit) = counts.iter().position(|&presses| presses < limit) else {
+        return best;
+      };
+      counts[..digit].fill(0);
+      counts[digit] += 1;
+    }
+  }
+
+  #[test]
+  fn test_many_buttons() {
+    let (_, machines) = parse(
+      "Button A: X+3, Y+1
+Button B: X+1, Y+2, Cost=2
+Button C: X+2, Y+2
+Button D: X+0, Y+1, Cost=4
+Prize: X=17, Y=13
+
+Button A: X+4, Y+0
+Button B: X+0, Y+4
+Button C: X+2, Y+2
+Prize: X=7, Y=9
+
+Button X: X+5, Y+1, Cost=1
+Button Y: X+1, Y+5, Cost=1
+Button Z: X+3, Y+3, Cost=3
+Prize: X=24, Y=24",
+    )
+    .unwrap();
+    for machine in &machines {
+      let presses = solve(machine, &Options::default());
+      assert_eq!(brute_force(machine, 20), presses.as_ref().map(|p| p.tokens));
+      if let Some(presses) = presses {
+        let reached = presses
+          .counts
+          .iter()
+          .zip(&machine.buttons)
+          .fold(Prize::ZERO, |at, (&n, button)| at + button.step * n as i64);
+        assert_eq!(machine.prize, reached);
+      }
+    }
+  }
+
+  #[test]
+  fn test_unbounded_search() {
+    // Buttons pulling both ways on both axes, with nothing to stop the search
+    let tug = machine(&[(1, -1), (-1, 1), (1, 1)], (2, 2));
+    assert_eq!(None, solve(&tug, &Options::default()));
+    let limited = Options {
+      press_limit: Some(10),
+      ..Options::default()
+    };
+    assert_eq!(
+      brute_force(&tug, 10),
+      solve(&tug, &limited).map(|p| p.tokens)
+    );
+  }
+
+  #[test]
+  fn test_idle_buttons() {
+    // A button that goes nowhere mustn't stop the search, wherever it comes
+    let mut idle = ClawMachine {
+      buttons: vec![
+        Button::new('C', 0, 0),
+        Button::new('A', 1, 0),
+        Button::new('B', 0, 1),
+      ],
+      prize: Prize::new(2, 2),
+    };
+    for _ in 0..3 {
+      let presses = solve(&idle, &Options::default()).unwrap();
+      // Two presses of A at 3 tokens, two of B at 1
+      assert_eq!(8, presses.tokens);
+      assert_eq!(brute_force(&idle, 10), Some(presses.tokens));
+      let still = idle
+        .buttons
+        .iter()
+        .position(|button| button.step == Prize::ZERO)
+        .unwrap();
+      assert_eq!(0, presses.counts[still]);
+      idle.buttons.rotate_left(1);
+    }
+
+    // Unless pressing it pays, then there's no cheapest
+    idle.buttons[0].cost = -1;
+    assert_eq!(None, solve(&idle, &Options::default()));
+  }
+}
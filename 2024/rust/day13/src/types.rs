@@ -1,11 +1,43 @@
 use glam::I64Vec2;
 
-pub type Button = I64Vec2;
 pub type Prize = I64Vec2;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Button {
+  pub label: char,
+  // How far one press moves the claw
+  pub step: I64Vec2,
+  // Tokens per press
+  pub cost: i64,
+}
+
+impl Button {
+  // Pressing button A costs 3x more than pressing any other button
+  pub fn default_cost(label: char) -> i64 {
+    if label == 'A' {
+      3
+    } else {
+      1
+    }
+  }
+
+  pub fn new(label: char, x: i64, y: i64) -> Self {
+    Self {
+      label,
+      step: I64Vec2::new(x, y),
+      cost: Self::default_cost(label),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ClawMachine {
-  pub a: Button,
-  pub b: Button,
+  pub buttons: Vec<Button>,
   pub prize: Prize,
 }
+
+impl ClawMachine {
+  pub fn button(&self, label: char) -> Option<&Button> {
+    self.buttons.iter().find(|button| button.label == label)
+  }
+}
@@ -0,0 +1,199 @@
+use std::{
+  fs,
+  io::{self, Write},
+  ops::Range,
+  path::{Path, PathBuf},
+  thread,
+  time::Duration,
+};
+
+use gif::{Encoder, Frame, Repeat};
+use glam::IVec2;
+
+use crate::types::Arena;
+
+// This many robots on one cell and it's as bright as it gets
+const SATURATION: usize = 3;
+// Terminal shading, from empty up to saturated
+const SHADES: [char; SATURATION + 1] = [' ', '.', '+', '#'];
+
+// A run of seconds from the simulation, drawn one frame per second
+pub struct Animation<'a> {
+  arena: &'a Arena,
+  seconds: Range<i64>,
+  // Image pixels per arena cell, along each side
+  scale: usize,
+}
+
+impl<'a> Animation<'a> {
+  pub fn new(arena: &'a Arena, seconds: Range<i64>) -> Self {
+    Self {
+      arena,
+      seconds,
+      scale: 1,
+    }
+  }
+
+  pub fn scale(mut self, scale: usize) -> Self {
+    self.scale = scale.max(1);
+    self
+  }
+
+  fn dimensions(&self) -> (usize, usize) {
+    (
+      self.arena.width().max(0) as usize * self.scale,
+      self.arena.height().max(0) as usize * self.scale,
+    )
+  }
+
+  // Robots on each cell after t seconds, row by row
+  fn counts(&self, t: i64) -> Vec<usize> {
+    let (width, height) = (self.arena.width(), self.arena.height());
+    let mut counts = vec![0; (width.max(0) * height.max(0)) as usize];
+    for IVec2 { x, y } in self.arena.positions_at(t) {
+      counts[(y * width + x) as usize] += 1;
+    }
+    counts
+  }
+
+  // One greyscale byte per pixel, brighter where more robots are stacked up
+  pub fn density(&self, t: i64) -> Vec<u8> {
+    let width = self.arena.width().max(0) as usize;
+    let counts = self.counts(t);
+    let (image_width, image_height) = self.dimensions();
+    (0..image_width * image_height)
+      .map(|pixel| {
+        let (x, y) = (
+          pixel % image_width / self.scale,
+          pixel / image_width / self.scale,
+        );
+        (counts[y * width + x].min(SATURATION) * 255 / SATURATION) as u8
+      })
+      .collect()
+  }
+
+  // A binary PGM image of the arena after t seconds
+  pub fn pgm(&self, t: i64) -> Vec<u8> {
+    let (width, height) = self.dimensions();
+    let mut image = format!("P5\n{width} {height}\n255\n").into_bytes();
+    image.extend(self.density(t));
+    image
+  }
+
+  // Write frame_<second>.pgm into dir for every second, returning the paths
+  pub fn write_pgm_frames(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    self
+      .seconds
+      .clone()
+      .map(|t| {
+        let path = dir.join(format!("frame_{t:05}.pgm"));
+        fs::write(&path, self.pgm(t))?;
+        Ok(path)
+      })
+      .collect()
+  }
+
+  // An endlessly looping greyscale GIF, delay is in hundredths of a second per frame
+  pub fn write_gif<W: Write>(&self, writer: W, delay: u16) -> Result<(), gif::EncodingError> {
+    let (width, height) = self.dimensions();
+    let too_big = |_| io::Error::new(io::ErrorKind::InvalidInput, "arena too big for a GIF");
+    let (width, height) = (
+      u16::try_from(width).map_err(too_big)?,
+      u16::try_from(height).map_err(too_big)?,
+    );
+    let palette: Vec<u8> = (0..=255).flat_map(|grey| [grey, grey, grey]).collect();
+    let mut encoder = Encoder::new(writer, width, height, &palette)?;
+    encoder.set_repeat(Repeat::Infinite)?;
+    for t in self.seconds.clone() {
+      let mut frame = Frame::from_indexed_pixels(width, height, self.density(t), None);
+      frame.delay = delay;
+      encoder.write_frame(&frame)?;
+    }
+    Ok(())
+  }
+
+  // The arena after t seconds as shaded text, ignoring the scale
+  pub fn text(&self, t: i64) -> String {
+    let width = self.arena.width().max(0) as usize;
+    let mut text = String::new();
+    for row in self.counts(t).chunks(width.max(1)) {
+      text.extend(row.iter().map(|&count| SHADES[count.min(SATURATION)]));
+      text.push('\n');
+    }
+    text
+  }
+
+  // Play the frames in a terminal, redrawing from the top left each time
+  pub fn play<W: Write>(&self, out: &mut W, delay: Duration) -> io::Result<()> {
+    for t in self.seconds.clone() {
+      write!(out, "\x1b[H\x1b[2JAfter {t} seconds\n{}", self.text(t))?;
+      out.flush()?;
+      thread::sleep(delay);
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::Robot;
+
+  fn arena() -> Arena {
+    let robots = [((0, 0), (1, 0)), ((0, 0), (1, 1)), ((2, 1), (0, 0))]
+      .into_iter()
+      .map(|((px, py), (vx, vy))| Robot {
+        position: IVec2::new(px, py),
+        velocity: IVec2::new(vx, vy),
+      })
+      .collect();
+    Arena::new(IVec2::new(3, 2), robots)
+  }
+
+  #[test]
+  fn test_density() {
+    let arena = arena();
+    let animation = Animation::new(&arena, 0..3);
+    assert_eq!(vec![170, 0, 0, 0, 0, 85], animation.density(0));
+    assert_eq!("+  \n  .\n", animation.text(0));
+    assert_eq!(" . \n ..\n", animation.text(1));
+
+    let scaled = Animation::new(&arena, 0..3).scale(2);
+    assert_eq!(b"P5\n6 4\n255\n".as_slice(), &scaled.pgm(0)[..11]);
+    assert_eq!(11 + 6 * 4, scaled.pgm(0).len());
+    assert_eq!(170, scaled.density(0)[7]);
+  }
+
+  #[test]
+  fn test_gif() -> Result<(), gif::EncodingError> {
+    let arena = arena();
+    let mut bytes = vec![];
+    Animation::new(&arena, 0..4)
+      .scale(4)
+      .write_gif(&mut bytes, 10)?;
+    assert!(bytes.starts_with(b"GIF89a"));
+
+    let mut decoder = gif::DecodeOptions::new();
+    decoder.set_color_output(gif::ColorOutput::Indexed);
+    let mut decoder = decoder.read_info(bytes.as_slice()).unwrap();
+    let mut frames = 0;
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+      assert_eq!((12, 8), (frame.width, frame.height));
+      frames += 1;
+    }
+    assert_eq!(4, frames);
+    Ok(())
+  }
+
+  #[test]
+  fn test_pgm_frames() -> io::Result<()> {
+    let arena = arena();
+    let dir = std::env::temp_dir().join(format!("day14-frames-{}", std::process::id()));
+    let paths = Animation::new(&arena, 5..8).write_pgm_frames(&dir)?;
+    assert_eq!(3, paths.len());
+    assert!(paths[0].ends_with("frame_00005.pgm"));
+    assert_eq!(Animation::new(&arena, 0..1).pgm(6), fs::read(&paths[1])?);
+    fs::remove_dir_all(dir)
+  }
+}
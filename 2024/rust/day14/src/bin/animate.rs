@@ -0,0 +1,64 @@
+use std::{fs::File, io::BufWriter, path::Path, time::Duration};
+
+use day14::{
+  animation::Animation,
+  detector::{detect, Scorer},
+  parsers::parse,
+};
+use miette::{miette, Context, IntoDiagnostic};
+
+const USAGE: &str =
+  "usage: animate <input file> <first second> <last second> [gif file | pgm directory | play]";
+
+// Usage: animate <input file> <first second> <last second> [gif file | pgm directory | play]
+// With no output it plays in the terminal. A second of "detect" centres the
+// frames on the Easter egg, e.g. animate input1.txt detect 10 egg.gif
+fn main() -> miette::Result<()> {
+  tracing_subscriber::fmt::init();
+  let mut args = std::env::args().skip(1);
+  let path = args.next().ok_or_else(|| miette!(USAGE))?;
+  let input = std::fs::read_to_string(&path)
+    .into_diagnostic()
+    .wrap_err_with(|| format!("reading {path}"))?;
+  let (remaining, arena) = parse(&input).map_err(|e| miette!("Error parsing {}", e))?;
+  if !remaining.trim().is_empty() {
+    return Err(miette!("Unexpected input after the robots {remaining:?}"));
+  }
+
+  let first = args.next().ok_or_else(|| miette!(USAGE))?;
+  let last = args.next().ok_or_else(|| miette!(USAGE))?;
+  let last: i64 = last.parse().into_diagnostic().context("last second")?;
+  let seconds = if first == "detect" {
+    let detection =
+      detect(&arena, Scorer::Variance).ok_or_else(|| miette!("No picture in the arena"))?;
+    // Here `last` is how many seconds to show either side
+    (detection.second - last)..(detection.second + last + 1)
+  } else {
+    let first: i64 = first.parse().into_diagnostic().context("first second")?;
+    first..(last + 1)
+  };
+
+  match args.next() {
+    Some(out) if out.ends_with(".gif") => {
+      let file = File::create(&out)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("creating {out}"))?;
+      Animation::new(&arena, seconds)
+        .scale(4)
+        .write_gif(BufWriter::new(file), 10)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("writing {out}"))?;
+    }
+    Some(out) if out != "play" => {
+      let frames = Animation::new(&arena, seconds)
+        .write_pgm_frames(Path::new(&out))
+        .into_diagnostic()
+        .wrap_err_with(|| format!("writing frames to {out}"))?;
+      println!("wrote {} frames to {out}", frames.len());
+    }
+    _ => Animation::new(&arena, seconds)
+      .play(&mut std::io::stdout(), Duration::from_millis(100))
+      .into_diagnostic()?,
+  }
+  Ok(())
+}
@@ -0,0 +1,198 @@
+use std::{cmp::Reverse, collections::HashSet};
+
+use glam::IVec2;
+
+use crate::types::Arena;
+
+// Ways of telling a picture from noise
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scorer {
+  // The robots bunch up along X and along Y at the same time. Each axis repeats
+  // on its own period, so the two best seconds are combined with the CRT
+  Variance,
+  // The frame where robots are spread over the fewest blocks of the arena
+  Entropy,
+  // The frame with the biggest clump of touching robots
+  LargestComponent,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Detection {
+  pub second: i64,
+  pub frame: String,
+}
+
+// Blocks are this many cells square when measuring entropy
+const BLOCK: i32 = 4;
+
+fn gcd(a: i64, b: i64) -> i64 {
+  if b == 0 {
+    a
+  } else {
+    gcd(b, a % b)
+  }
+}
+
+// The smallest t >= 0 with t = a mod m and t = b mod n, or None if there
+// isn't one. Arenas are small, so stepping through a's residues is plenty
+fn crt(a: i64, m: i64, b: i64, n: i64) -> Option<i64> {
+  (0..n / gcd(m, n))
+    .map(|k| a.rem_euclid(m) + k * m)
+    .find(|t| (t - b).rem_euclid(n) == 0)
+}
+
+fn variance(values: impl Iterator<Item = i32>) -> f64 {
+  let values: Vec<f64> = values.map(f64::from).collect();
+  let count = values.len().max(1) as f64;
+  let mean = values.iter().sum::<f64>() / count;
+  values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count
+}
+
+// The second, within the axis' own period, where the robots are bunched up tightest along it
+fn tightest(arena: &Arena, period: i32, axis: fn(IVec2) -> i32) -> i64 {
+  (0..period as i64)
+    .map(|t| (t, variance(arena.positions_at(t).map(axis))))
+    .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    .map_or(0, |(t, _)| t)
+}
+
+// Shannon entropy of how the robots are shared out between blocks; lower is more ordered
+fn entropy(arena: &Arena, t: i64) -> f64 {
+  let blocks = (arena.size + BLOCK - 1) / BLOCK;
+  let mut counts = vec![0usize; (blocks.x * blocks.y) as usize];
+  for position in arena.positions_at(t) {
+    let block = position / BLOCK;
+    counts[(block.y * blocks.x + block.x) as usize] += 1;
+  }
+  let total = arena.robots.len().max(1) as f64;
+  counts
+    .into_iter()
+    .filter(|&count| count > 0)
+    .map(|count| {
+      let p = count as f64 / total;
+      -p * p.log2()
+    })
+    .sum()
+}
+
+// The most robots in one group of cells joined up horizontally or vertically
+fn largest_component(arena: &Arena, t: i64) -> usize {
+  let mut unvisited: HashSet<IVec2> = arena.positions_at(t).collect();
+  let mut largest = 0;
+  while let Some(&start) = unvisited.iter().next() {
+    unvisited.remove(&start);
+    let mut stack = vec![start];
+    let mut size = 0;
+    while let Some(cell) = stack.pop() {
+      size += 1;
+      for step in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+        if unvisited.remove(&(cell + step)) {
+          stack.push(cell + step);
+        }
+      }
+    }
+    largest = largest.max(size);
+  }
+  largest
+}
+
+// The first second in the arena's period where the robots look most like a picture
+pub fn detect(arena: &Arena, scorer: Scorer) -> Option<Detection> {
+  if arena.robots.is_empty() || arena.width() <= 0 || arena.height() <= 0 {
+    return None;
+  }
+  let seconds = 0..arena.period();
+  let second = match scorer {
+    Scorer::Variance => {
+      let tx = tightest(arena, arena.width(), |p| p.x);
+      let ty = tightest(arena, arena.height(), |p| p.y);
+      crt(tx, arena.width() as i64, ty, arena.height() as i64)?
+    }
+    Scorer::Entropy => {
+      seconds
+        .map(|t| (t, entropy(arena, t)))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))?
+        .0
+    }
+    Scorer::LargestComponent => {
+      seconds.max_by_key(|&t| (largest_component(arena, t), Reverse(t)))?
+    }
+  };
+  Some(Detection {
+    second,
+    frame: arena.render(second),
+  })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use super::*;
+  use crate::types::Robot;
+  use rstest::rstest;
+
+  // An arena where the robots draw a filled diamond at `second`, with some
+  // stragglers scattered around. Velocities come from a fixed LCG
+  pub(crate) fn hidden_picture(size: IVec2, second: i64) -> Arena {
+    let mut state = 0x2024_u64;
+    let mut random = |below: i32| {
+      state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+      ((state >> 33) % below as u64) as i32
+    };
+    let centre = size / 2;
+    let mut targets = vec![];
+    for y in -6..=6 {
+      for x in -6..=6 {
+        if i32::abs(x) + i32::abs(y) <= 6 {
+          targets.push(centre + IVec2::new(x, y));
+        }
+      }
+    }
+    for _ in 0..30 {
+      targets.push(IVec2::new(random(size.x), random(size.y)));
+    }
+    let robots = targets
+      .into_iter()
+      .map(|target| {
+        let velocity = IVec2::new(random(2 * size.x) - size.x, random(2 * size.y) - size.y);
+        // Run the robot backwards from where it has to be
+        let reverse = Robot {
+          position: target,
+          velocity: -velocity,
+        };
+        Robot {
+          position: reverse.position_at(size, second),
+          velocity,
+        }
+      })
+      .collect();
+    Arena::new(size, robots)
+  }
+
+  #[test]
+  fn test_crt() {
+    assert_eq!(Some(7502), crt(28, 101, 86, 103));
+    assert_eq!(Some(10), crt(4, 6, 2, 4));
+    assert_eq!(None, crt(1, 6, 2, 4));
+  }
+
+  #[rstest]
+  #[case(Scorer::Variance)]
+  #[case(Scorer::Entropy)]
+  #[case(Scorer::LargestComponent)]
+  fn test_detect(#[case] scorer: Scorer) {
+    for (size, second) in [(IVec2::new(31, 37), 500), (IVec2::new(41, 43), 1234)] {
+      let arena = hidden_picture(size, second);
+      let detection = detect(&arena, scorer).unwrap();
+      assert_eq!(second, detection.second);
+      assert!(detection.frame.contains("#############"));
+    }
+  }
+
+  #[test]
+  fn test_nothing_to_detect() {
+    let empty = Arena::new(IVec2::new(11, 7), vec![]);
+    assert_eq!(None, detect(&empty, Scorer::Variance));
+  }
+}
@@ -1,4 +1,9 @@
+pub mod animation;
+pub mod detector;
 pub mod parsers;
 pub mod part1;
 pub mod part2;
 pub mod types;
+
+#[cfg(test)]
+generators::fuzz_tests!(14);
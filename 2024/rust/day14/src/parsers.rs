@@ -4,16 +4,31 @@ use nom::{
   self,
   bytes::complete::tag,
   character::complete::{self, line_ending},
+  combinator::{opt, verify},
   multi::separated_list1,
-  sequence::{preceded, separated_pair},
+  sequence::{preceded, separated_pair, terminated},
   IResult, Parser,
 };
 
-use crate::types::Robot;
+use crate::types::{Arena, Robot, PUZZLE_SIZE};
 
-pub fn parse(input: &str) -> IResult<&str, Vec<Robot>> {
-  let (remaining, result) = separated_list1(line_ending, robot)(input)?;
-  Ok((remaining, result))
+// The arena is the puzzle size unless the input starts with a header like "size=11,7".
+// Robots wrap round the arena, so both sides have to be at least 1
+pub fn parse(input: &str) -> IResult<&str, Arena> {
+  let (input, size) = opt(terminated(arena_size, line_ending))(input)?;
+  let (remaining, robots) = separated_list1(line_ending, robot)(input)?;
+  Ok((remaining, Arena::new(size.unwrap_or(PUZZLE_SIZE), robots)))
+}
+
+fn arena_size(input: &str) -> IResult<&str, IVec2> {
+  preceded(
+    tag("size="),
+    verify(
+      separated_pair(complete::i32, tag(","), complete::i32),
+      |&(x, y)| x > 0 && y > 0,
+    )
+    .map(|(x, y)| IVec2::new(x, y)),
+  )(input)
 }
 
 fn robot(input: &str) -> IResult<&str, Robot> {
@@ -40,3 +55,36 @@ fn velocity(input: &str) -> IResult<&str, IVec2> {
     separated_pair(complete::i32, tag(","), complete::i32).map(|(x, y)| IVec2::new(x, y)),
   )(input)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_arena_size() {
+    let (_, arena) = parse("p=0,4 v=3,-3\np=6,3 v=-1,-3").unwrap();
+    assert_eq!(PUZZLE_SIZE, arena.size);
+    assert_eq!(2, arena.robots.len());
+
+    let (_, arena) = parse("size=11,7\np=0,4 v=3,-3").unwrap();
+    assert_eq!((11, 7), (arena.width(), arena.height()));
+    assert_eq!(
+      vec![Robot {
+        position: IVec2::new(0, 4),
+        velocity: IVec2::new(3, -3)
+      }],
+      arena.robots
+    );
+    assert_eq!(Ok(("", arena.clone())), parse(&arena.to_string()));
+  }
+
+  #[test]
+  fn test_bad_arena_size() {
+    for header in ["size=0,0", "size=-3,5", "size=11,0"] {
+      assert!(
+        parse(&format!("{header}\np=0,0 v=1,1")).is_err(),
+        "{header}"
+      );
+    }
+  }
+}
@@ -1,13 +1,170 @@
-use glam::IVec2;
+use std::{collections::HashSet, fmt, ops::Range};
 
-pub const GRID_SIZE: IVec2 = if cfg!(test) {
-  IVec2::new(11, 7)
-} else {
-  IVec2::new(101, 103)
-};
+use glam::{I64Vec2, IVec2};
 
-#[derive(Debug)]
+// The size of the real puzzle arena, when the input doesn't say otherwise
+pub const PUZZLE_SIZE: IVec2 = IVec2::new(101, 103);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Robot {
   pub position: IVec2,
   pub velocity: IVec2,
 }
+
+impl Robot {
+  // Where the robot is after t seconds, wrapping around an arena of the given size
+  pub fn position_at(&self, size: IVec2, t: i64) -> IVec2 {
+    let size = size.as_i64vec2();
+    // Every robot is back where it started after `size` seconds along each axis,
+    // so only the remainder matters and nothing can overflow
+    let t = I64Vec2::splat(t).rem_euclid(size);
+    (self.position.as_i64vec2() + self.velocity.as_i64vec2() * t)
+      .rem_euclid(size)
+      .as_ivec2()
+  }
+}
+
+impl fmt::Display for Robot {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let (p, v) = (self.position, self.velocity);
+    write!(f, "p={},{} v={},{}", p.x, p.y, v.x, v.y)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arena {
+  pub size: IVec2,
+  pub robots: Vec<Robot>,
+}
+
+impl Arena {
+  pub fn new(size: IVec2, robots: Vec<Robot>) -> Self {
+    Self { size, robots }
+  }
+
+  pub fn width(&self) -> i32 {
+    self.size.x
+  }
+
+  pub fn height(&self) -> i32 {
+    self.size.y
+  }
+
+  pub fn positions_at(&self, t: i64) -> impl Iterator<Item = IVec2> + '_ {
+    self
+      .robots
+      .iter()
+      .map(move |robot| robot.position_at(self.size, t))
+  }
+
+  // Robots inside the given columns and rows after t seconds
+  pub fn count_in(&self, t: i64, xs: Range<i32>, ys: Range<i32>) -> usize {
+    self
+      .positions_at(t)
+      .filter(|position| xs.contains(&position.x) && ys.contains(&position.y))
+      .count()
+  }
+
+  // Robots in each quadrant after t seconds, top left, top right, bottom left
+  // then bottom right. Robots on the middle row or column aren't in any of them
+  pub fn quadrants(&self, t: i64) -> [usize; 4] {
+    let divider = self.size / 2;
+    let (left, right) = (0..divider.x, (self.size.x - divider.x)..self.size.x);
+    let (top, bottom) = (0..divider.y, (self.size.y - divider.y)..self.size.y);
+    [
+      self.count_in(t, left.clone(), top.clone()),
+      self.count_in(t, right.clone(), top),
+      self.count_in(t, left, bottom.clone()),
+      self.count_in(t, right, bottom),
+    ]
+  }
+
+  pub fn safety_factor(&self, t: i64) -> usize {
+    self.quadrants(t).iter().product()
+  }
+
+  // Every robot is back where it started after this many seconds
+  pub fn period(&self) -> i64 {
+    let (width, height) = (self.width() as i64, self.height() as i64);
+    let gcd = |mut a: i64, mut b: i64| {
+      while b != 0 {
+        (a, b) = (b, a % b);
+      }
+      a
+    };
+    width / gcd(width, height) * height
+  }
+
+  // The arena after t seconds, '#' wherever there's at least one robot
+  pub fn render(&self, t: i64) -> String {
+    let occupied: HashSet<IVec2> = self.positions_at(t).collect();
+    let mut frame = String::new();
+    for y in 0..self.height() {
+      for x in 0..self.width() {
+        frame.push(if occupied.contains(&IVec2::new(x, y)) {
+          '#'
+        } else {
+          '.'
+        });
+      }
+      frame.push('\n');
+    }
+    frame
+  }
+}
+
+// Written out in the input format, with the size header so it reads back the same
+impl fmt::Display for Arena {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "size={},{}", self.width(), self.height())?;
+    for robot in &self.robots {
+      write!(f, "\n{robot}")?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_position_at() {
+    let robot = Robot {
+      position: IVec2::new(2, 4),
+      velocity: IVec2::new(2, -3),
+    };
+    let size = IVec2::new(11, 7);
+    // The robot's path from the puzzle description
+    let path = [(2, 4), (4, 1), (6, 5), (8, 2), (10, 6), (1, 3)];
+    for (t, (x, y)) in path.into_iter().enumerate() {
+      assert_eq!(IVec2::new(x, y), robot.position_at(size, t as i64));
+    }
+    assert_eq!(robot.position, robot.position_at(size, 77));
+    assert_eq!(
+      robot.position_at(size, 3),
+      robot.position_at(size, 77 * 1_000_000 + 3)
+    );
+    assert_eq!(robot.position_at(size, -1), robot.position_at(size, 76));
+  }
+
+  #[test]
+  fn test_regions() {
+    let robots = [(0, 0), (10, 0), (5, 3), (5, 0), (0, 6), (9, 5), (10, 6)]
+      .into_iter()
+      .map(|(x, y)| Robot {
+        position: IVec2::new(x, y),
+        velocity: IVec2::ZERO,
+      })
+      .collect();
+    let arena = Arena::new(IVec2::new(11, 7), robots);
+    assert_eq!([1, 1, 1, 2], arena.quadrants(0));
+    assert_eq!(2, arena.safety_factor(1000));
+    assert_eq!(3, arena.count_in(0, 5..11, 0..4));
+    assert_eq!(77, arena.period());
+    assert_eq!(
+      "#....#....#\n...........\n...........\n.....#.....\n...........\n.........#.\n#.........#\n",
+      arena.render(0)
+    );
+  }
+}
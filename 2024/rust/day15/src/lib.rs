@@ -1,3 +1,9 @@
+pub mod parsers;
 pub mod part1;
 pub mod part2;
+pub mod replay;
+pub mod solver;
 pub mod types;
+
+#[cfg(test)]
+generators::fuzz_tests!(15);
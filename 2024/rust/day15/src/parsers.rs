@@ -0,0 +1,347 @@
+use glam::I64Vec2;
+use miette::{Diagnostic, LabeledSpan};
+use nom::{
+  character::complete::{line_ending, satisfy},
+  combinator::consumed,
+  multi::{many0, many1, separated_list1},
+  sequence::pair,
+  IResult, Parser,
+};
+use nom_locate::LocatedSpan;
+use thiserror::Error;
+
+use crate::types::{Moves, Warehouse};
+
+type Span<'a> = LocatedSpan<&'a str>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseErrorKind {
+  #[error("no warehouse map")]
+  MissingMap,
+  #[error("expected a blank line between the map and the moves")]
+  MissingBlankLine,
+  #[error("unknown tile {0:?}")]
+  UnknownTile(char),
+  #[error("unknown move {0:?}")]
+  UnknownMove(char),
+  #[error("box isn't closed off with ]")]
+  BrokenBox,
+  #[error("no robot in the warehouse")]
+  MissingRobot,
+  #[error("more than one robot in the warehouse")]
+  MultipleRobots,
+  #[error("row is {found} tiles wide, the first row is {expected}")]
+  NotRectangular { expected: usize, found: usize },
+  #[error("gap in the warehouse's outer wall")]
+  OpenBorder,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("{kind}")]
+#[diagnostic(code(day15::parse))]
+pub struct ParseError {
+  pub kind: ParseErrorKind,
+  #[source_code]
+  src: String,
+  #[label(collection)]
+  labels: Vec<LabeledSpan>,
+}
+
+impl ParseError {
+  fn new(input: &str, kind: ParseErrorKind, labels: Vec<LabeledSpan>) -> Self {
+    Self {
+      kind,
+      src: input.to_string(),
+      labels,
+    }
+  }
+
+  fn at(input: &str, kind: ParseErrorKind, offset: usize, label: &str) -> Self {
+    Self::new(
+      input,
+      kind,
+      vec![LabeledSpan::new(Some(label.to_string()), offset, 1)],
+    )
+  }
+}
+
+// A character, and where it is in the input
+type Located = (usize, char);
+
+fn located(input: Span) -> IResult<Span, Located> {
+  consumed(satisfy(|c| c != '\r' && c != '\n'))
+    .map(|(span, chr): (Span, char)| (span.location_offset(), chr))
+    .parse(input)
+}
+
+fn map_rows(input: Span) -> IResult<Span, Vec<Vec<Located>>> {
+  separated_list1(line_ending, many1(located))(input)
+}
+
+fn blank_line(input: Span) -> IResult<Span, ()> {
+  pair(line_ending, line_ending).map(|_| ()).parse(input)
+}
+
+// Every move character, line endings skipped
+fn moves(input: Span) -> IResult<Span, Vec<Located>> {
+  many0(located.map(Some).or(line_ending.map(|_| None)))
+    .map(|moves| moves.into_iter().flatten().collect())
+    .parse(input)
+}
+
+fn check_map(input: &str, rows: &[Vec<Located>]) -> Result<(), ParseError> {
+  // Without the blank line the moves look like more of the map
+  if let Some(row) = rows
+    .iter()
+    .skip(1)
+    .find(|row| row.iter().all(|&(_, chr)| "<>^v".contains(chr)))
+  {
+    return Err(ParseError::at(
+      input,
+      ParseErrorKind::MissingBlankLine,
+      row[0].0,
+      "moves start here",
+    ));
+  }
+
+  let expected = rows[0].len();
+  if let Some(row) = rows.iter().find(|row| row.len() != expected) {
+    return Err(ParseError::new(
+      input,
+      ParseErrorKind::NotRectangular {
+        expected,
+        found: row.len(),
+      },
+      vec![LabeledSpan::new(
+        Some("this row".to_string()),
+        row[0].0,
+        row.len(),
+      )],
+    ));
+  }
+
+  let mut robots = vec![];
+  for row in rows {
+    let mut in_box = None;
+    for &(offset, chr) in row {
+      match (chr, in_box) {
+        ('#' | '.' | 'O' | '@', None) | (']', Some(_)) => in_box = None,
+        ('[', None) | ('=', Some(_)) => in_box = in_box.or(Some(offset)),
+        ('#' | '.' | 'O' | '@' | '[', Some(start)) => {
+          return Err(ParseError::at(
+            input,
+            ParseErrorKind::BrokenBox,
+            start,
+            "box starts here",
+          ))
+        }
+        ('=' | ']', None) => {
+          return Err(ParseError::at(
+            input,
+            ParseErrorKind::BrokenBox,
+            offset,
+            "no [ before this",
+          ))
+        }
+        (chr, _) => {
+          return Err(ParseError::at(
+            input,
+            ParseErrorKind::UnknownTile(chr),
+            offset,
+            "expected one of #.O@[]",
+          ))
+        }
+      }
+      if chr == '@' {
+        robots.push(offset);
+      }
+    }
+    if let Some(start) = in_box {
+      return Err(ParseError::at(
+        input,
+        ParseErrorKind::BrokenBox,
+        start,
+        "box starts here",
+      ));
+    }
+  }
+
+  match robots[..] {
+    [] => {
+      let end = rows
+        .last()
+        .and_then(|row| row.last())
+        .map_or(0, |c| c.0 + 1);
+      return Err(ParseError::new(
+        input,
+        ParseErrorKind::MissingRobot,
+        vec![LabeledSpan::new(Some("in this map".to_string()), 0, end)],
+      ));
+    }
+    [_] => (),
+    [first, second, ..] => {
+      return Err(ParseError::new(
+        input,
+        ParseErrorKind::MultipleRobots,
+        vec![
+          LabeledSpan::new(Some("one here".to_string()), first, 1),
+          LabeledSpan::new(Some("another here".to_string()), second, 1),
+        ],
+      ))
+    }
+  }
+
+  let last = rows.len() - 1;
+  let border = rows.iter().enumerate().flat_map(|(row, cells)| {
+    cells
+      .iter()
+      .enumerate()
+      .filter(move |&(col, _)| row == 0 || row == last || col == 0 || col == cells.len() - 1)
+      .map(|(_, cell)| cell)
+  });
+  for &(offset, chr) in border {
+    if chr != '#' {
+      return Err(ParseError::at(
+        input,
+        ParseErrorKind::OpenBorder,
+        offset,
+        "should be a wall",
+      ));
+    }
+  }
+  Ok(())
+}
+
+// The map, a blank line, then the robot's moves, with either style of line
+// ending. Each map cell is stretched `scale` cells wide, so 1 reads the map as
+// drawn and 2 is the wide warehouse
+pub fn parse(input: &str, scale: i64) -> Result<(Moves, Warehouse), ParseError> {
+  let span = Span::new(input);
+  let (rest, rows) = map_rows(span)
+    .map_err(|_| ParseError::at(input, ParseErrorKind::MissingMap, 0, "expected a map here"))?;
+  check_map(input, &rows)?;
+
+  let (rest, _) = blank_line(rest).map_err(|_| {
+    ParseError::at(
+      input,
+      ParseErrorKind::MissingBlankLine,
+      rest.location_offset(),
+      "map ends here",
+    )
+  })?;
+  // Only a stray carriage return can stop the moves early
+  let (rest, located_moves) = moves(rest).unwrap_or((rest, vec![]));
+  if let Some(chr) = rest.fragment().chars().next() {
+    return Err(ParseError::at(
+      input,
+      ParseErrorKind::UnknownMove(chr),
+      rest.location_offset(),
+      "expected one of <>^v",
+    ));
+  }
+
+  let mut moves = Moves::with_capacity(located_moves.len());
+  for (offset, chr) in located_moves {
+    moves.push(match chr {
+      '>' => Warehouse::RIGHT,
+      'v' => Warehouse::DOWN,
+      '<' => Warehouse::LEFT,
+      '^' => Warehouse::UP,
+      _ => {
+        return Err(ParseError::at(
+          input,
+          ParseErrorKind::UnknownMove(chr),
+          offset,
+          "expected one of <>^v",
+        ))
+      }
+    });
+  }
+
+  let map: String = rows
+    .iter()
+    .map(|row| {
+      row
+        .iter()
+        .map(|&(_, chr)| chr)
+        .chain(['\n'])
+        .collect::<String>()
+    })
+    .collect();
+  Ok((moves, Warehouse::scaled(&map, scale)))
+}
+
+// How a move is written in the input
+pub fn symbol(direction: I64Vec2) -> char {
+  match direction {
+    Warehouse::RIGHT => '>',
+    Warehouse::DOWN => 'v',
+    Warehouse::LEFT => '<',
+    _ => '^',
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rstest::rstest;
+
+  const MAP: &str = "########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########";
+
+  #[test]
+  fn test_line_endings() {
+    let unix = format!("{MAP}\n\n<^^>>>vv\n<v>>v<<");
+    let windows = unix.replace('\n', "\r\n");
+    let (moves, warehouse) = parse(&unix, 1).unwrap();
+    assert_eq!(15, moves.len());
+    assert_eq!(I64Vec2::new(2, 2), warehouse.robot);
+    assert_eq!((8, 8), (warehouse.height, warehouse.width));
+    let (windows_moves, windows_warehouse) = parse(&windows, 1).unwrap();
+    assert_eq!(moves, windows_moves);
+    assert_eq!(warehouse, windows_warehouse);
+    // No moves at all is fine
+    assert!(parse(&format!("{MAP}\n\n"), 2).unwrap().0.is_empty());
+  }
+
+  #[rstest]
+  #[case(MAP.replacen("..O", "..X", 1), ParseErrorKind::UnknownTile('X'), 12)]
+  #[case(MAP.replace('@', "."), ParseErrorKind::MissingRobot, 0)]
+  #[case(MAP.replacen("..O", "..@", 1), ParseErrorKind::MultipleRobots, 12)]
+  #[case(MAP.replacen("#...O..#", "#...O...#", 1), ParseErrorKind::NotRectangular { expected: 8, found: 9 }, 27)]
+  #[case(MAP.replacen("#......#", "#.......", 1), ParseErrorKind::OpenBorder, 61)]
+  #[case(MAP.replacen(".O.O", ".[=.", 1), ParseErrorKind::BrokenBox, 12)]
+  #[case(MAP.replacen(".O.O", ".O=]", 1), ParseErrorKind::BrokenBox, 13)]
+  fn test_bad_map(#[case] map: String, #[case] kind: ParseErrorKind, #[case] offset: usize) {
+    let error = parse(&format!("{map}\n\n<>"), 1).unwrap_err();
+    assert_eq!(kind, error.kind);
+    assert_eq!(offset, error.labels[0].offset());
+  }
+
+  #[test]
+  fn test_bad_moves() {
+    assert_eq!(ParseErrorKind::MissingMap, parse("", 1).unwrap_err().kind);
+
+    let error = parse(&format!("{MAP}\n\n<^^>\n>x>vv"), 1).unwrap_err();
+    assert_eq!(ParseErrorKind::UnknownMove('x'), error.kind);
+    assert_eq!(MAP.len() + 2 + 6, error.labels[0].offset());
+
+    let error = parse(&format!("{MAP}\n<^^>"), 1).unwrap_err();
+    assert_eq!(ParseErrorKind::MissingBlankLine, error.kind);
+    assert_eq!(MAP.len() + 1, error.labels[0].offset());
+  }
+
+  #[test]
+  fn test_wide_map() {
+    // Drawing a stretched warehouse and reading it back as drawn changes nothing
+    let (_, stretched) = parse(&format!("{MAP}\n\n"), 2).unwrap();
+    let (_, redrawn) = parse(&format!("{}\n", stretched.map()), 1).unwrap();
+    assert_eq!(stretched, redrawn);
+  }
+}
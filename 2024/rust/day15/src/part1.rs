@@ -1,39 +1,14 @@
-use crate::types::{Moves, Warehouse};
+use crate::parsers::parse;
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-  let (moves, mut warehouse) = parse(input);
+  let (moves, mut warehouse) = parse(input, 1)?;
   for robot_direction in moves {
-    let new_position = warehouse.robot + robot_direction;
-    if warehouse.try_move(&new_position, &robot_direction) {
-      warehouse.robot = new_position;
-    }
+    warehouse.move_robot(robot_direction);
   }
-  // println!("{}", &warehouse);
   Ok(warehouse.box_score().to_string())
 }
 
-fn parse(input: &str) -> (Moves, Warehouse) {
-  let (warehouse, moves) = input.split_once("\n\n").unwrap_or_default();
-  let warehouse = Warehouse::from(warehouse);
-  let moves: Moves = moves
-    .lines()
-    .flat_map(|line| {
-      line
-        .chars()
-        .map(|ch| match ch {
-          '>' => Warehouse::RIGHT,
-          'v' => Warehouse::DOWN,
-          '<' => Warehouse::LEFT,
-          '^' => Warehouse::UP,
-          _ => unreachable!(),
-        })
-        .collect::<Moves>()
-    })
-    .collect();
-  (moves, warehouse)
-}
-
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -0,0 +1,263 @@
+use glam::I64Vec2;
+
+use crate::{
+  parsers::symbol,
+  types::{BoxId, Moves, Warehouse},
+};
+
+// Moves per line when writing a snapshot, like the puzzle's example
+const LINE_LENGTH: usize = 70;
+
+// One move that has been played, with enough to take it back again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+  pub direction: I64Vec2,
+  // The boxes that moved, or None if the robot was blocked
+  pub pushed: Option<Vec<BoxId>>,
+}
+
+// Plays the robot's moves one at a time, remembering each so they can be undone
+#[derive(Debug, Clone)]
+pub struct Replay {
+  warehouse: Warehouse,
+  moves: Moves,
+  history: Vec<Step>,
+}
+
+impl Replay {
+  pub fn new(warehouse: Warehouse, moves: Moves) -> Self {
+    Self {
+      warehouse,
+      moves,
+      history: vec![],
+    }
+  }
+
+  // The warehouse as it is after `position()` moves
+  pub fn warehouse(&self) -> &Warehouse {
+    &self.warehouse
+  }
+
+  // How many moves have been played
+  pub fn position(&self) -> usize {
+    self.history.len()
+  }
+
+  pub fn len(&self) -> usize {
+    self.moves.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.moves.is_empty()
+  }
+
+  pub fn last_step(&self) -> Option<&Step> {
+    self.history.last()
+  }
+
+  // Play the next move, or None if there are none left
+  pub fn step(&mut self) -> Option<&Warehouse> {
+    let direction = *self.moves.get(self.position())?;
+    let pushed = self.warehouse.push(direction);
+    self.history.push(Step { direction, pushed });
+    Some(&self.warehouse)
+  }
+
+  // Take back the last move played, returning false if there wasn't one
+  pub fn undo(&mut self) -> bool {
+    let Some(step) = self.history.pop() else {
+      return false;
+    };
+    if let Some(pushed) = step.pushed {
+      self.warehouse.pull_back(step.direction, &pushed);
+    }
+    true
+  }
+
+  // Go forwards or backwards to the state after `moves` moves, or the last move
+  // if there aren't that many
+  pub fn seek(&mut self, moves: usize) -> &Warehouse {
+    let moves = moves.min(self.len());
+    while self.position() > moves {
+      self.undo();
+    }
+    while self.position() < moves {
+      self.step();
+    }
+    &self.warehouse
+  }
+
+  pub fn run(&mut self) -> &Warehouse {
+    self.seek(self.len())
+  }
+
+  // The current state written as puzzle input, with the moves still to come,
+  // so a run can be picked up again from here
+  pub fn snapshot(&self) -> String {
+    let mut snapshot = self.warehouse.map();
+    let remaining: Vec<char> = self.moves[self.position()..]
+      .iter()
+      .map(|&direction| symbol(direction))
+      .collect();
+    for line in remaining.chunks(LINE_LENGTH) {
+      snapshot.push('\n');
+      snapshot.extend(line);
+    }
+    if remaining.is_empty() {
+      snapshot.push('\n');
+    }
+    snapshot
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parsers::parse;
+  use proptest::{prelude::*, test_runner::RngSeed};
+  use std::collections::HashSet;
+
+  const INPUT: &str = "##########
+#..O..O.O#
+#......O.#
+#.OO..O.O#
+#..O@..O.#
+#O#..O...#
+#O..O..O.#
+#.OO.O.OO#
+#....O...#
+##########
+
+<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
+vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
+><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
+<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
+^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
+^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
+>^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
+<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
+^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
+v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
+
+  #[test]
+  fn test_undo_everything() {
+    let (moves, warehouse) = parse(INPUT, 2).unwrap();
+    let mut replay = Replay::new(warehouse.clone(), moves);
+    assert_eq!(9021, replay.run().box_score());
+    assert_eq!(None, replay.step());
+    while replay.undo() {}
+    assert_eq!(0, replay.position());
+    assert_eq!(&warehouse, replay.warehouse());
+  }
+
+  #[test]
+  fn test_seek() {
+    let (moves, warehouse) = parse(INPUT, 1).unwrap();
+    let mut replay = Replay::new(warehouse, moves);
+    let after_300 = replay.seek(300).clone();
+    replay.seek(700);
+    assert_eq!(&after_300, replay.seek(300));
+
+    // Blocked moves are remembered too
+    let robot = replay.seek(13).robot;
+    replay.seek(14);
+    assert_eq!(
+      Some(&Step {
+        direction: Warehouse::DOWN,
+        pushed: None,
+      }),
+      replay.last_step()
+    );
+    assert_eq!(robot, replay.warehouse().robot);
+    // Two boxes in a row shoved right
+    replay.seek(17);
+    assert_eq!(
+      Some(2),
+      replay.last_step().unwrap().pushed.as_ref().map(Vec::len)
+    );
+  }
+
+  #[rstest::rstest]
+  #[case(1, 10092)]
+  #[case(2, 9021)]
+  fn test_snapshot(#[case] scale: i64, #[case] expected: i64) {
+    let (moves, warehouse) = parse(INPUT, scale).unwrap();
+    let mut replay = Replay::new(warehouse, moves);
+    for position in [0, 123, 700] {
+      replay.seek(position);
+      let (moves, warehouse) = parse(&replay.snapshot(), 1).unwrap();
+      assert_eq!(replay.len() - position, moves.len());
+      assert_eq!(replay.warehouse().map(), warehouse.map());
+      assert_eq!(expected, Replay::new(warehouse, moves).run().box_score());
+    }
+    let finished = replay.run().clone();
+    let (moves, warehouse) = parse(&replay.snapshot(), 1).unwrap();
+    assert!(moves.is_empty());
+    assert_eq!(finished.map(), warehouse.map());
+  }
+
+  // A walled warehouse up to 8 by 8 inside, drawn narrow, with the robot
+  // somewhere and a list of moves
+  fn warehouse() -> impl Strategy<Value = (String, Moves)> {
+    (1..9usize, 1..9usize)
+      .prop_flat_map(|(rows, cols)| {
+        (
+          prop::collection::vec(
+            prop::sample::select(vec!['.', '.', '.', 'O', 'O', '#']),
+            rows * cols,
+          ),
+          0..rows * cols,
+          Just(cols),
+          prop::collection::vec(
+            prop::sample::select(vec![
+              Warehouse::UP,
+              Warehouse::RIGHT,
+              Warehouse::DOWN,
+              Warehouse::LEFT,
+            ]),
+            0..80,
+          ),
+        )
+      })
+      .prop_map(|(mut cells, robot, cols, moves)| {
+        cells[robot] = '@';
+        let wall = "#".repeat(cols + 2);
+        let mut map = vec![wall.clone()];
+        map.extend(
+          cells
+            .chunks(cols)
+            .map(|row| format!("#{}#", row.iter().collect::<String>())),
+        );
+        map.push(wall);
+        (map.join("\n"), moves)
+      })
+  }
+
+  proptest! {
+    #![proptest_config(ProptestConfig {
+      rng_seed: RngSeed::Fixed(15),
+      ..ProptestConfig::default()
+    })]
+
+    // Boxes only ever get shoved about: none go missing or change size, none
+    // end up in a wall, on each other or under the robot, and undoing every
+    // move puts the warehouse back as it was
+    #[test]
+    fn test_boxes_conserved((map, moves) in warehouse(), scale in 1..3i64) {
+      let warehouse = Warehouse::scaled(&map, scale);
+      let widths = |warehouse: &Warehouse| warehouse.boxes.iter().map(|b| b.width).collect::<Vec<_>>();
+      let mut replay = Replay::new(warehouse.clone(), moves);
+      while let Some(current) = replay.step() {
+        prop_assert_eq!(widths(&warehouse), widths(current));
+        let cells: Vec<_> = current.boxes.iter().flat_map(|b| b.cells()).collect();
+        let distinct: HashSet<_> = cells.iter().collect();
+        prop_assert_eq!(cells.len(), distinct.len());
+        prop_assert!(cells.iter().all(|cell| !current.walls.contains(cell)));
+        prop_assert!(!distinct.contains(&current.robot));
+        prop_assert!(!current.walls.contains(&current.robot));
+      }
+      while replay.undo() {}
+      prop_assert_eq!(&warehouse, replay.warehouse());
+    }
+  }
+}
@@ -0,0 +1,259 @@
+use std::{
+  cmp::Reverse,
+  collections::{BinaryHeap, HashSet},
+};
+
+use glam::I64Vec2;
+
+use crate::types::{Moves, Package, Warehouse};
+
+// What the boxes should end up looking like
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Goal {
+  // Every box's left hand cell on one of these, one box per target
+  Targets(HashSet<I64Vec2>),
+  // A box_score of at least this much
+  MinScore(i64),
+}
+
+impl Goal {
+  fn reached(&self, warehouse: &Warehouse) -> bool {
+    match self {
+      Goal::Targets(targets) => warehouse.boxes.iter().all(|b| targets.contains(&b.left)),
+      Goal::MinScore(score) => warehouse.box_score() >= *score,
+    }
+  }
+
+  // A lower bound on the moves still needed. One move shifts each box at most
+  // one cell, so it's the furthest any box is from its nearest target
+  fn estimate(&self, warehouse: &Warehouse) -> usize {
+    let Goal::Targets(targets) = self else {
+      return 0;
+    };
+    warehouse
+      .boxes
+      .iter()
+      .map(|package| {
+        targets
+          .iter()
+          .map(|target| (*target - package.left).abs().element_sum() as usize)
+          .min()
+          .unwrap_or_default()
+      })
+      .max()
+      .unwrap_or_default()
+  }
+
+  // A box that can never move again and isn't on a target means the goal
+  // can't be reached any more
+  fn deadlocked(&self, warehouse: &Warehouse) -> bool {
+    let Goal::Targets(targets) = self else {
+      return false;
+    };
+    warehouse
+      .boxes
+      .iter()
+      .any(|package| !targets.contains(&package.left) && cornered(warehouse, package))
+  }
+}
+
+// Wedged into a corner: the robot has nowhere to stand to shove the box out
+// of it, whatever else happens to be in the way
+fn cornered(warehouse: &Warehouse, package: &Package) -> bool {
+  let wall = |cell: I64Vec2| warehouse.walls.contains(&cell);
+  let walled = |side: I64Vec2| package.cells().all(|cell| wall(cell + side));
+  let right_end = package.left + I64Vec2::new(0, package.width - 1);
+  (walled(Warehouse::UP) || walled(Warehouse::DOWN))
+    && (wall(package.left + Warehouse::LEFT) || wall(right_end + Warehouse::RIGHT))
+}
+
+// Boxes of the same width are interchangeable, so a state is the robot
+// and the sorted boxes. The walls never change, so they aren't part of it
+type State = (I64Vec2, Vec<Package>);
+
+fn state(warehouse: &Warehouse) -> State {
+  let mut boxes = warehouse.boxes.clone();
+  boxes.sort_unstable_by_key(|package| (package.width, package.left.x, package.left.y));
+  (warehouse.robot, boxes)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+  // The shortest list of moves that reaches the goal
+  Solved(Moves),
+  // Every layout has been tried, and none of them reach the goal
+  Unsolvable,
+  // Stopped after looking at `max_states` layouts, without an answer either way
+  GaveUp,
+}
+
+// The shortest list of moves that gets the boxes to the goal, using A* with the
+// estimate above. Gives up once `max_states` warehouse layouts have been looked at
+pub fn solve(warehouse: &Warehouse, goal: &Goal, max_states: usize) -> Outcome {
+  if let Goal::Targets(targets) = goal {
+    if targets.len() != warehouse.boxes.len() {
+      return Outcome::Unsolvable;
+    }
+  }
+  let directions = [
+    Warehouse::UP,
+    Warehouse::RIGHT,
+    Warehouse::DOWN,
+    Warehouse::LEFT,
+  ];
+
+  // Every state found so far, with the one it was reached from and how
+  let start = state(warehouse);
+  let mut seen = HashSet::from([start.clone()]);
+  let mut states: Vec<(State, Option<(usize, I64Vec2)>)> = vec![(start, None)];
+
+  // (estimated total, moves so far, index into states)
+  let mut queue = BinaryHeap::new();
+  queue.push(Reverse((goal.estimate(warehouse), 0, 0)));
+
+  // One warehouse, and so one set of walls, laid out afresh for each state
+  let mut current = warehouse.clone();
+  while let Some(Reverse((_, moves, index))) = queue.pop() {
+    let (robot, boxes) = states[index].0.clone();
+    current.rearrange(robot, boxes);
+    if goal.reached(&current) {
+      let mut path = vec![];
+      let mut index = index;
+      while let Some((previous, direction)) = states[index].1 {
+        path.push(direction);
+        index = previous;
+      }
+      path.reverse();
+      return Outcome::Solved(path);
+    }
+    for direction in directions {
+      let Some(pushed) = current.push(direction) else {
+        continue;
+      };
+      let next = state(&current);
+      let deadlocked = goal.deadlocked(&current);
+      let estimate = goal.estimate(&current);
+      current.pull_back(direction, &pushed);
+      if deadlocked || seen.contains(&next) {
+        continue;
+      }
+      if seen.len() >= max_states {
+        return Outcome::GaveUp;
+      }
+      seen.insert(next.clone());
+      queue.push(Reverse((moves + 1 + estimate, moves + 1, states.len())));
+      states.push((next, Some((index, direction))));
+    }
+  }
+  Outcome::Unsolvable
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn targets(cells: &[(i64, i64)]) -> Goal {
+    Goal::Targets(cells.iter().map(|&(x, y)| I64Vec2::new(x, y)).collect())
+  }
+
+  fn solved(outcome: Outcome) -> Moves {
+    match outcome {
+      Outcome::Solved(moves) => moves,
+      outcome => panic!("no moves found: {outcome:?}"),
+    }
+  }
+
+  fn play(warehouse: &Warehouse, moves: &Moves) -> Warehouse {
+    let mut warehouse = warehouse.clone();
+    for &direction in moves {
+      warehouse.move_robot(direction);
+    }
+    warehouse
+  }
+
+  #[test]
+  fn test_narrow_box() {
+    let warehouse = Warehouse::from(
+      "#######
+#.....#
+#.@O..#
+#.....#
+#######",
+    );
+    let goal = targets(&[(1, 4)]);
+    let moves = solved(solve(&warehouse, &goal, 10_000));
+    // > then round underneath and ^
+    assert_eq!(4, moves.len());
+    assert!(goal.reached(&play(&warehouse, &moves)));
+    // Four moves away is more than one layout
+    assert_eq!(Outcome::GaveUp, solve(&warehouse, &goal, 1));
+  }
+
+  #[test]
+  fn test_deadlock() {
+    let warehouse = Warehouse::from(
+      "######
+#O...#
+#..@.#
+#....#
+######",
+    );
+    let stuck = targets(&[(3, 4)]);
+    assert!(stuck.deadlocked(&warehouse));
+    // Pruning means there's nothing to search, so it's found to be unsolvable
+    // well within the limit
+    assert_eq!(Outcome::Unsolvable, solve(&warehouse, &stuck, 1));
+    assert_eq!(
+      Outcome::Solved(vec![]),
+      solve(&warehouse, &targets(&[(1, 1)]), 1)
+    );
+    // The wrong number of targets can never work
+    assert_eq!(
+      Outcome::Unsolvable,
+      solve(&warehouse, &targets(&[(1, 1), (2, 2)]), 1000)
+    );
+  }
+
+  #[test]
+  fn test_wide_boxes() {
+    let warehouse = Warehouse::scaled(
+      "#######
+#.....#
+#.O.O.#
+#..@..#
+#######",
+      2,
+    );
+    // Both boxes up against the top wall, and the right hand one nudged over
+    let goal = targets(&[(1, 4), (1, 9)]);
+    let moves = solved(solve(&warehouse, &goal, 100_000));
+    let finished = play(&warehouse, &moves);
+    assert!(goal.reached(&finished));
+    // <^ for the left box, >>> to shove the right one along, then v>^
+    assert_eq!(8, moves.len(), "{}", finished.map());
+
+    // A wide box wedged into the corner
+    let wedged = Warehouse::scaled("####\n#O.#\n#.@#\n####", 2);
+    assert!(targets(&[(2, 2)]).deadlocked(&wedged));
+  }
+
+  #[test]
+  fn test_min_score() {
+    let warehouse = Warehouse::from(
+      "######
+#....#
+#.O@.#
+#....#
+######",
+    );
+    assert_eq!(202, warehouse.box_score());
+    // Push the box down a row
+    let moves = solved(solve(&warehouse, &Goal::MinScore(302), 10_000));
+    assert_eq!(3, moves.len());
+    assert_eq!(302, play(&warehouse, &moves).box_score());
+    assert_eq!(
+      Outcome::Unsolvable,
+      solve(&warehouse, &Goal::MinScore(10_000), 10_000)
+    );
+  }
+}
@@ -1,90 +1,56 @@
 use glam::I64Vec2;
 use std::{
-  collections::{HashMap, HashSet},
+  collections::{HashMap, HashSet, VecDeque},
   fmt::Display,
 };
 
-// Part 1
-
 pub type Moves = Vec<I64Vec2>;
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum Item {
-  Box,
-  Wall,
+// Index into Warehouse::boxes
+pub type BoxId = usize;
+
+// A box lying along a row, from `left` across `width` cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Package {
+  pub left: I64Vec2,
+  pub width: i64,
 }
 
-impl Display for Item {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    match self {
-      Item::Box => write!(f, "O"),
-      Item::Wall => write!(f, "#"),
-    }
+impl Package {
+  pub fn cells(&self) -> impl Iterator<Item = I64Vec2> + '_ {
+    (0..self.width).map(|col| self.left + I64Vec2::new(0, col))
   }
-}
 
-type Grid = HashMap<I64Vec2, Item>;
+  // GPS coordinate, measured from the box's left hand edge
+  pub fn gps(&self) -> i64 {
+    self.left.x * 100 + self.left.y
+  }
+}
 
+// Positions are (row, col), so x runs down the map and y across it
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Warehouse {
   pub height: i64,
   pub width: i64,
-  pub floorplan: Grid,
+  pub walls: HashSet<I64Vec2>,
+  pub boxes: Vec<Package>,
   pub robot: I64Vec2,
+  // Which box, if any, covers each cell
+  index: HashMap<I64Vec2, BoxId>,
 }
 
+// The map exactly as drawn, one cell per character
 impl From<&str> for Warehouse {
   fn from(input: &str) -> Self {
-    let mut floorplan = Grid::new();
-    let mut robot = I64Vec2::ZERO;
-    let mut rows = 0;
-    let mut cols = 0;
-
-    for (row, line) in input.lines().enumerate() {
-      for (col, chr) in line.chars().enumerate() {
-        let pos = I64Vec2::new(row as i64, col as i64);
-
-        if chr == '#' {
-          floorplan.insert(pos, Item::Wall);
-        } else if chr == 'O' {
-          floorplan.insert(pos, Item::Box);
-        } else if chr == '@' {
-          robot = pos;
-        }
-
-        rows = if pos.x > rows { pos.x } else { rows };
-        cols = if pos.y > cols { pos.y } else { cols };
-      }
-    }
-
-    Self {
-      height: rows as i64,
-      width: cols as i64,
-      floorplan,
-      robot,
-    }
+    Self::scaled(input, 1)
   }
 }
 
 impl Display for Warehouse {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    writeln!(f, "Warehouse height {}", self.height + 1)?;
-    writeln!(f, "Warehouse width {}", self.width + 1)?;
-
-    for row in 0..=self.height {
-      for col in 0..=self.width {
-        let pos = I64Vec2::new(row, col);
-        if pos == self.robot {
-          write!(f, "@")?;
-          continue;
-        };
-        match self.floorplan.get(&pos) {
-          None => write!(f, ".")?,
-          Some(item) => write!(f, "{item}")?,
-        }
-      }
-      writeln!(f)?;
-    }
-    writeln!(f)?;
+    writeln!(f, "Warehouse height {}", self.height)?;
+    writeln!(f, "Warehouse width {}", self.width)?;
+    writeln!(f, "{}", self.map())?;
     Ok(())
   }
 }
@@ -95,309 +61,256 @@ impl Warehouse {
   pub const LEFT: I64Vec2 = I64Vec2::NEG_Y;
   pub const UP: I64Vec2 = I64Vec2::NEG_X;
 
-  pub fn try_move(&mut self, pos: &I64Vec2, direction: &I64Vec2) -> bool {
-    match self.floorplan.get(pos) {
-      None => true,
-      Some(Item::Wall) => false,
-      Some(Item::Box) => {
-        let next_position = pos + direction;
-        if self.try_move(&next_position, direction) {
-          self.floorplan.remove(pos);
-          self.floorplan.insert(next_position, Item::Box);
-          true
-        } else {
-          false
+  pub fn new(size: I64Vec2, walls: HashSet<I64Vec2>, boxes: Vec<Package>, robot: I64Vec2) -> Self {
+    let mut warehouse = Self {
+      height: size.x,
+      width: size.y,
+      walls,
+      boxes: vec![],
+      robot,
+      index: HashMap::new(),
+    };
+    warehouse.rearrange(robot, boxes);
+    warehouse
+  }
+
+  // Read a map, stretching every cell `scale` times wider: walls get wider,
+  // each O becomes a box `scale` cells wide and the robot keeps to the left
+  // hand cell. Boxes already drawn wide, like [] or [==], are stretched too
+  pub fn scaled(input: &str, scale: i64) -> Self {
+    let scale = scale.max(1);
+    let mut walls = HashSet::new();
+    let mut boxes = vec![];
+    let mut robot = I64Vec2::ZERO;
+    let mut size = I64Vec2::ZERO;
+
+    for (row, line) in input.lines().enumerate() {
+      let mut col = 0;
+      for chr in line.chars() {
+        let pos = I64Vec2::new(row as i64, col);
+        match chr {
+          '#' => walls.extend((0..scale).map(|offset| pos + I64Vec2::new(0, offset))),
+          'O' => boxes.push(Package {
+            left: pos,
+            width: scale,
+          }),
+          '[' => boxes.push(Package {
+            left: pos,
+            width: 0,
+          }),
+          '@' => robot = pos,
+          _ => (),
         }
+        if matches!(chr, '[' | '=' | ']') {
+          if let Some(package) = boxes.last_mut() {
+            package.width += scale;
+          }
+        }
+        col += scale;
       }
+      size = size.max(I64Vec2::new(row as i64 + 1, col));
     }
+    Self::new(size, walls, boxes, robot)
   }
 
-  pub fn box_score(&self) -> i64 {
-    self
-      .floorplan
+  // Put the robot and the boxes somewhere else, keeping the walls
+  pub fn rearrange(&mut self, robot: I64Vec2, boxes: Vec<Package>) {
+    self.index = boxes
       .iter()
-      .filter_map(|(pos, item)| {
-        if *item == Item::Box {
-          Some(pos.x * 100 + pos.y)
-        } else {
-          None
-        }
-      })
-      .sum()
+      .enumerate()
+      .flat_map(|(id, package)| package.cells().map(move |cell| (cell, id)))
+      .collect();
+    self.boxes = boxes;
+    self.robot = robot;
   }
-}
 
-// Part 2
-
-#[derive(Debug, PartialEq, Eq)]
-pub enum WideItem {
-  BoxLeft,
-  BoxRight,
-  Wall,
-}
+  // The box covering a cell
+  pub fn box_at(&self, cell: I64Vec2) -> Option<BoxId> {
+    self.index.get(&cell).copied()
+  }
 
-impl Display for WideItem {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    match self {
-      WideItem::BoxLeft => write!(f, "["),
-      WideItem::BoxRight => write!(f, "]"),
-      WideItem::Wall => write!(f, "#"),
+  // Every box that moves if something shoves `start` one step in `direction`,
+  // nearest first, or None if any of them would be pushed into a wall
+  pub fn pushed_by(&self, start: I64Vec2, direction: I64Vec2) -> Option<Vec<BoxId>> {
+    let mut queue = VecDeque::from([start]);
+    let mut seen = HashSet::new();
+    let mut pushed = vec![];
+    while let Some(cell) = queue.pop_front() {
+      if self.walls.contains(&cell) {
+        return None;
+      }
+      let Some(id) = self.box_at(cell) else {
+        continue;
+      };
+      if seen.insert(id) {
+        pushed.push(id);
+        queue.extend(
+          self.boxes[id]
+            .cells()
+            .map(|cell| cell + direction)
+            .filter(|next| self.box_at(*next) != Some(id)),
+        );
+      }
     }
+    Some(pushed)
   }
-}
-
-type WideGrid = HashMap<I64Vec2, WideItem>;
-
-pub struct WideWarehouse {
-  pub height: i64,
-  pub width: i64,
-  pub floorplan: WideGrid,
-  pub robot: I64Vec2,
-}
 
-impl From<&str> for WideWarehouse {
-  fn from(input: &str) -> Self {
-    let mut floorplan = WideGrid::new();
-    let mut robot = I64Vec2::ZERO;
-    let mut rows = 0;
-    let mut cols = 0;
-
-    let expand_warehouse: Vec<Vec<char>> = input
-      .lines()
-      .map(|line| {
-        line
-          .chars()
-          .flat_map(|c| match c {
-            '#' => vec!['#', '#'],
-            'O' => vec!['[', ']'],
-            '@' => vec!['@', '.'],
-            '.' => vec!['.', '.'],
-            _ => unreachable!(),
-          })
-          .collect()
-      })
-      .collect();
-
-    for (row, line) in expand_warehouse.iter().enumerate() {
-      for (col, chr) in line.iter().enumerate() {
-        let pos = I64Vec2::new(row as i64, col as i64);
-
-        if *chr == '#' {
-          floorplan.insert(pos, WideItem::Wall);
-        } else if *chr == '[' {
-          floorplan.insert(pos, WideItem::BoxLeft);
-        } else if *chr == ']' {
-          floorplan.insert(pos, WideItem::BoxRight);
-        } else if *chr == '@' {
-          robot = pos;
-        }
-
-        rows = if pos.x > rows { pos.x } else { rows };
-        cols = if pos.y > cols { pos.y } else { cols };
+  fn shift(&mut self, boxes: &[BoxId], by: I64Vec2) {
+    for &id in boxes {
+      for cell in self.boxes[id].cells() {
+        self.index.remove(&cell);
       }
     }
-
-    Self {
-      height: rows as i64,
-      width: cols as i64,
-      floorplan,
-      robot,
+    for &id in boxes {
+      self.boxes[id].left += by;
+      let package = self.boxes[id];
+      self.index.extend(package.cells().map(|cell| (cell, id)));
     }
   }
-}
 
-impl Display for WideWarehouse {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    writeln!(f, "Warehouse height {}", self.height + 1)?;
-    writeln!(f, "Warehouse width {}", self.width + 1)?;
+  // Step the robot, pushing whatever is in the way. All the boxes move
+  // together or not at all. Returns the boxes that moved, or None if the
+  // robot couldn't
+  pub fn push(&mut self, direction: I64Vec2) -> Option<Vec<BoxId>> {
+    let next = self.robot + direction;
+    let pushed = self.pushed_by(next, direction)?;
+    self.shift(&pushed, direction);
+    self.robot = next;
+    Some(pushed)
+  }
 
-    for row in 0..=self.height {
-      for col in 0..=self.width {
-        let pos = I64Vec2::new(row, col);
-        if pos == self.robot {
-          write!(f, "@")?;
-          continue;
-        };
-        match self.floorplan.get(&pos) {
-          None => write!(f, ".")?,
-          Some(item) => write!(f, "{item}")?,
-        }
-      }
-      writeln!(f)?;
-    }
-    writeln!(f)?;
-    Ok(())
+  // Take back a push in `direction` that moved the `pushed` boxes
+  pub fn pull_back(&mut self, direction: I64Vec2, pushed: &[BoxId]) {
+    self.shift(pushed, -direction);
+    self.robot -= direction;
   }
-}
 
-impl WideWarehouse {
-  pub const RIGHT: I64Vec2 = I64Vec2::Y;
-  pub const DOWN: I64Vec2 = I64Vec2::X;
-  pub const LEFT: I64Vec2 = I64Vec2::NEG_Y;
-  pub const UP: I64Vec2 = I64Vec2::NEG_X;
+  // Returns whether the robot moved
+  pub fn move_robot(&mut self, direction: I64Vec2) -> bool {
+    self.push(direction).is_some()
+  }
 
-  pub fn try_big_move(&mut self, pos: I64Vec2, direction: I64Vec2) -> bool {
-    match direction.x {
-      0 => {
-        // Moving horizontally
-        match self.floorplan.get(&pos) {
-          None => true,
-          Some(WideItem::Wall) => false,
-          Some(WideItem::BoxRight) => {
-            let next_position = pos + direction;
-            if self.try_big_move(next_position, direction) {
-              self.floorplan.remove(&pos);
-              self.floorplan.insert(next_position, WideItem::BoxRight);
-              true
-            } else {
-              false
-            }
-          }
-          Some(WideItem::BoxLeft) => {
-            let next_position = pos + direction;
-            if self.try_big_move(next_position, direction) {
-              self.floorplan.remove(&pos);
-              self.floorplan.insert(next_position, WideItem::BoxLeft);
-              true
-            } else {
-              false
-            }
+  pub fn box_score(&self) -> i64 {
+    self.boxes.iter().map(Package::gps).sum()
+  }
+
+  // The map in the puzzle's own notation
+  pub fn map(&self) -> String {
+    let mut map = String::new();
+    for row in 0..self.height {
+      for col in 0..self.width {
+        let pos = I64Vec2::new(row, col);
+        let chr = if pos == self.robot {
+          '@'
+        } else if self.walls.contains(&pos) {
+          '#'
+        } else if let Some(id) = self.box_at(pos) {
+          match self.boxes[id] {
+            Package { width: 1, .. } => 'O',
+            Package { left, .. } if left == pos => '[',
+            Package { left, width } if left.y + width - 1 == col => ']',
+            _ => '=',
           }
-        }
-      }
-      -1 | 1 => {
-        /*
-         * Moving upwards or downwards
-         *
-         * call function to return a bool, list of coords to move in direction
-         *
-         * we have the current pos and dir as inputs
-         *
-         * function must travel up or down all boxes in the stack
-         *   and assess if there is room at the end to move all of them
-         *
-         * function needs to know if it assessing a BoxLeft or BoxRight
-         *   as it moves up and down the stack of boxes.
-         *
-         * If we hit a wall along the way - abandon the move and return
-         *   false with an empty movelist
-         */
-        let (allowed, mut movelist) = match self.floorplan.get(&pos) {
-          None => return true,
-          Some(item) => match item {
-            WideItem::Wall => return false,
-            WideItem::BoxLeft => self.find_all_boxes_that_can_move(pos, direction),
-            WideItem::BoxRight => {
-              let left_side = pos + Self::LEFT;
-              self.find_all_boxes_that_can_move(left_side, direction)
-            }
-          },
+        } else {
+          '.'
         };
-        if allowed {
-          while !movelist.is_empty() {
-            let moveset = movelist.drain(..).collect::<HashSet<_>>();
-            for left_side in moveset {
-              let right_side = left_side + Self::RIGHT;
-              let next_box_left = left_side + direction;
-              let next_box_right = left_side + Self::RIGHT + direction;
-              if self.floorplan.contains_key(&next_box_left)
-                || self.floorplan.contains_key(&next_box_right)
-              {
-                movelist.push(left_side);
-              } else {
-                assert_eq!(self.floorplan.get(&left_side), Some(&WideItem::BoxLeft));
-                self.floorplan.remove(&left_side);
-                self.floorplan.remove(&right_side);
-                self.floorplan.insert(next_box_left, WideItem::BoxLeft);
-                self.floorplan.insert(next_box_right, WideItem::BoxRight);
-              }
-            }
-          }
-        }
-        allowed
+        map.push(chr);
       }
-      _ => unreachable!(
-        "We got an instruction to move in direction that wasn't up, down, left or right"
-      ),
+      map.push('\n');
     }
+    map
   }
+}
 
-  fn find_all_boxes_that_can_move(
-    &self,
-    mut side: I64Vec2,
-    direction: I64Vec2,
-  ) -> (bool, Vec<I64Vec2>) {
-    // Return (allowed, Vec<BoxLeft coords>)
-    if Some(&WideItem::BoxRight) == self.floorplan.get(&side) {
-      // Reposition ourselves on a BoxLeft, if needed
-      side = side + Self::LEFT;
-    }
-    let next_position_left = side + direction;
-    let next_position_right = side + direction + Self::RIGHT;
-    // Peek at what is sitting in the next position along
-    // from our current position (side)
-    match (
-      self.floorplan.get(&next_position_left),
-      self.floorplan.get(&next_position_right),
-    ) {
-      // Empty space
-      // We can move both sides of the box in our current position
-      (None, None) => (true, vec![side]),
-      // Oops, there is a wall
-      // We can't move
-      (_, Some(WideItem::Wall)) | (Some(WideItem::Wall), _) => (false, vec![]),
-      // Found another box
-      // Check whether we are looking at BoxLeft or BoxRight
-      // and then peek at one more step in the direction of travel
-      (left, right) => {
-        let mut movelist = vec![side];
-        let (can_move, v) = match left {
-          None => (true, vec![]),
-          Some(WideItem::BoxRight) => {
-            // We looked left, but found a BoxRight
-            // So reposition one more step to the left and check again
-            let one_more_step_left = next_position_left + Self::LEFT;
-            self.find_all_boxes_that_can_move(one_more_step_left, direction)
-          }
-          Some(WideItem::BoxLeft) => {
-            self.find_all_boxes_that_can_move(next_position_left, direction)
-          }
-          _ => unreachable!(),
-        };
-        if !can_move {
-          return (false, v);
-        }
-        movelist.extend(v);
-        let (can_move, v) = match right {
-          None => (true, vec![]),
-          Some(WideItem::BoxRight) => (true, vec![]),
-          Some(WideItem::BoxLeft) => {
-            // We looked right, but found a BoxLeft
-            // So keep moving in the direction of travel and check again
-            self.find_all_boxes_that_can_move(next_position_right, direction)
-          }
-          _ => unreachable!(),
-        };
-        if !can_move {
-          return (false, v);
-        }
-        movelist.extend(v);
-        (true, movelist)
-      }
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn run(warehouse: &mut Warehouse, moves: &str) {
+    for chr in moves.chars() {
+      let direction = match chr {
+        '>' => Warehouse::RIGHT,
+        'v' => Warehouse::DOWN,
+        '<' => Warehouse::LEFT,
+        _ => Warehouse::UP,
+      };
+      warehouse.move_robot(direction);
     }
   }
 
-  pub fn box_score(&self) -> i64 {
-    self
-      .floorplan
-      .iter()
-      .filter_map(|(pos, item)| {
-        if *item == WideItem::BoxLeft {
-          Some(pos.x * 100 + pos.y)
-        } else {
-          None
-        }
-      })
-      .sum()
+  #[test]
+  fn test_wide_boxes() {
+    let mut warehouse = Warehouse::scaled(
+      "#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######",
+      2,
+    );
+    run(&mut warehouse, "<vv<<^^<<^^");
+    assert_eq!(
+      "##############
+##...[].##..##
+##...@.[]...##
+##....[]....##
+##..........##
+##..........##
+##############
+",
+      warehouse.map()
+    );
+    assert_eq!(105 + 207 + 306, warehouse.box_score());
+  }
+
+  #[test]
+  fn test_any_width() {
+    // A box three cells wide, resting on two narrow ones
+    let mut warehouse = Warehouse::from(
+      "########
+#......#
+#.[=]..#
+#.O.O..#
+#..@...#
+#......#
+########",
+    );
+    assert_eq!(3, warehouse.boxes.len());
+    // Pushing up on the left hand box moves the wide box too, but not the other O
+    assert_eq!(
+      Some(vec![1, 0]),
+      warehouse.pushed_by(I64Vec2::new(3, 2), Warehouse::UP)
+    );
+    run(&mut warehouse, "<^");
+    assert_eq!(
+      "########
+#.[=]..#
+#.O....#
+#.@.O..#
+#......#
+#......#
+########
+",
+      warehouse.map()
+    );
+    // Now the stack is against the wall
+    run(&mut warehouse, "^");
+    assert_eq!(I64Vec2::new(3, 2), warehouse.robot);
+    // Shoving the wide box sideways from its end
+    run(&mut warehouse, ">>^>^<");
+    assert_eq!(
+      "########
+#[=]@..#
+#.O....#
+#....O.#
+#......#
+#......#
+########
+",
+      warehouse.map()
+    );
+    assert_eq!(Warehouse::from(warehouse.map().as_str()), warehouse);
   }
 }
@@ -1,3 +1,4 @@
+use day16::types::{Maze, MazeCosts};
 use day16::*;
 
 fn main() {
@@ -14,3 +15,46 @@ fn part1() {
 fn part2() {
   part2::process(divan::black_box(include_str!("../input2.txt"))).unwrap();
 }
+
+// An empty room with S and E in opposite corners. With turning free every
+// route up and across is a cheapest one, and there are a lot of those
+fn open_maze(size: usize) -> Maze {
+  let mut input = "#".repeat(size + 2) + "\n";
+  for row in 0..size {
+    input.push('#');
+    for col in 0..size {
+      input.push(match (row, col) {
+        (0, col) if col == size - 1 => 'E',
+        (row, 0) if row == size - 1 => 'S',
+        _ => '.',
+      });
+    }
+    input.push_str("#\n");
+  }
+  input.push_str(&"#".repeat(size + 2));
+  Maze::try_from(input.as_str())
+    .unwrap()
+    .with_costs(MazeCosts {
+      turn: 0,
+      ..Default::default()
+    })
+}
+
+#[divan::bench(args = [4, 6, 8, 10])]
+fn open_maze_all_paths(bencher: divan::Bencher, size: usize) {
+  let maze = open_maze(size);
+  bencher.bench(|| divan::black_box(&maze).all_shortest_paths().unwrap());
+}
+
+#[divan::bench(args = [4, 6, 8, 10, 100])]
+fn open_maze_best_seats(bencher: divan::Bencher, size: usize) {
+  let maze = open_maze(size);
+  bencher.bench(|| divan::black_box(&maze).best_seats().unwrap());
+}
+
+// Random mazes with loops in, as big as the real input and bigger
+#[divan::bench(args = [41, 141, 281])]
+fn generated_maze_best_seats(bencher: divan::Bencher, size: usize) {
+  let input = generators::inputs::reindeer_maze(size, 16);
+  bencher.bench(|| part2::process(divan::black_box(&input)).unwrap());
+}
@@ -0,0 +1,35 @@
+use day16::types::Maze;
+use miette::{miette, Context, IntoDiagnostic};
+
+// Usage: routes <input file> [how many]
+// Draws the cheapest few routes through the maze with what each costs, then
+// the best seats
+fn main() -> miette::Result<()> {
+  tracing_subscriber::fmt::init();
+  let mut args = std::env::args().skip(1);
+  let path = args
+    .next()
+    .ok_or_else(|| miette!("usage: routes <input file> [how many]"))?;
+  let input = std::fs::read_to_string(&path)
+    .into_diagnostic()
+    .wrap_err_with(|| format!("reading {path}"))?;
+  let count: usize = match args.next() {
+    Some(count) => count.parse().into_diagnostic().context("how many")?,
+    None => 3,
+  };
+
+  let maze = Maze::try_from(input.as_str())?;
+  let routes = maze.cheapest_routes(count);
+  if routes.is_empty() {
+    return Err(miette!("No path found for this maze"));
+  }
+  for (number, (path, cost)) in routes.iter().enumerate() {
+    println!("Route {} costs {cost}", number + 1);
+    println!("{}", maze.render_path(path));
+  }
+  if let Some((cost, seats)) = maze.best_seats() {
+    println!("{} best seats on routes costing {cost}", seats.len());
+    println!("{}", maze.render_seats(&seats));
+  }
+  Ok(())
+}
@@ -1,2 +1,6 @@
 pub mod part1;
 pub mod part2;
+pub mod types;
+
+#[cfg(test)]
+generators::fuzz_tests!(16);
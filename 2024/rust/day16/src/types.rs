@@ -0,0 +1,557 @@
+use glam::I64Vec2;
+use miette::miette;
+use std::{
+  cmp::Reverse,
+  collections::{BinaryHeap, HashMap, HashSet},
+  fmt::Display,
+};
+
+// Positions are (row, col), so x runs down the maze and y across it
+pub const NORTH: I64Vec2 = I64Vec2::NEG_X;
+pub const EAST: I64Vec2 = I64Vec2::Y;
+pub const SOUTH: I64Vec2 = I64Vec2::X;
+pub const WEST: I64Vec2 = I64Vec2::NEG_Y;
+
+// Every heading, clockwise from north an eighth of a turn at a time
+const HEADINGS: [I64Vec2; 8] = [
+  NORTH,
+  I64Vec2::new(-1, 1),
+  EAST,
+  I64Vec2::new(1, 1),
+  SOUTH,
+  I64Vec2::new(1, -1),
+  WEST,
+  I64Vec2::new(-1, -1),
+];
+
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy, Hash)]
+pub struct MazeTracker {
+  pub location: I64Vec2,
+  pub direction: I64Vec2,
+}
+
+// What the reindeer pays to get around
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MazeCosts {
+  // A step along one of the compass points
+  pub step: usize,
+  // Turning up to a right angle, on top of the step after it
+  pub turn: usize,
+  // Turning further than that, up to right round, or None if it can't be done
+  pub u_turn: Option<usize>,
+  // A diagonal step, or None to keep to the compass points
+  pub diagonal: Option<usize>,
+}
+
+// The puzzle's scoring
+impl Default for MazeCosts {
+  fn default() -> Self {
+    Self {
+      step: 1,
+      turn: 1000,
+      u_turn: None,
+      diagonal: None,
+    }
+  }
+}
+
+impl MazeCosts {
+  // Cheapest possible step, for the A* estimate
+  fn cheapest_step(&self) -> usize {
+    self
+      .diagonal
+      .map_or(self.step, |diagonal| diagonal.min(self.step))
+  }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Maze {
+  pub walls: HashSet<I64Vec2>,
+  // The last row and column
+  pub rows: i64,
+  pub cols: i64,
+  pub start: I64Vec2,
+  // Which way the reindeer faces at the start
+  pub heading: I64Vec2,
+  pub end: I64Vec2,
+  pub costs: MazeCosts,
+}
+
+// S is the start facing east. The start can be drawn as one of ^>v< instead
+// to face another way. Besides # and ., there has to be exactly one start and one E
+impl TryFrom<&str> for Maze {
+  type Error = miette::Report;
+
+  fn try_from(input: &str) -> miette::Result<Self> {
+    let mut walls = HashSet::new();
+    let mut start = None;
+    let mut heading = EAST;
+    let mut end = None;
+    let mut rows: i64 = 0;
+    let mut cols: i64 = 0;
+    for (row, line) in input.lines().enumerate() {
+      for (col, ch) in line.chars().enumerate() {
+        rows = rows.max(row as i64);
+        cols = cols.max(col as i64);
+
+        let location = I64Vec2::new(row as i64, col as i64);
+
+        match ch {
+          'S' | '^' | '>' | 'v' | '<' => {
+            if start.replace(location).is_some() {
+              return Err(miette!("More than one start, another at {row},{col}"));
+            }
+            heading = match ch {
+              '^' => NORTH,
+              'v' => SOUTH,
+              '<' => WEST,
+              _ => EAST,
+            };
+          }
+          'E' => {
+            if end.replace(location).is_some() {
+              return Err(miette!("More than one end, another at {row},{col}"));
+            }
+          }
+          '#' => {
+            walls.insert(location);
+          }
+          '.' => (),
+          _ => return Err(miette!("Unknown tile {ch:?} at {row},{col}")),
+        }
+      }
+    }
+    Ok(Self {
+      walls,
+      rows,
+      cols,
+      start: start.ok_or_else(|| miette!("No start in the maze"))?,
+      heading,
+      end: end.ok_or_else(|| miette!("No end in the maze"))?,
+      costs: MazeCosts::default(),
+    })
+  }
+}
+
+impl Display for Maze {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.draw(|_| None))
+  }
+}
+
+// How a step is drawn on the map
+fn arrow(direction: I64Vec2) -> char {
+  match direction.to_array() {
+    [-1, 0] => '^',
+    [0, 1] => '>',
+    [1, 0] => 'v',
+    [0, -1] => '<',
+    [-1, 1] | [1, -1] => '/',
+    _ => '\\',
+  }
+}
+
+impl Maze {
+  pub fn with_costs(mut self, costs: MazeCosts) -> Self {
+    self.costs = costs;
+    self
+  }
+
+  // Face the reindeer a different way from the one drawn
+  pub fn with_heading(mut self, heading: I64Vec2) -> Self {
+    self.heading = heading;
+    self
+  }
+
+  // The map, with `mark` choosing what goes on the open tiles other than S and E
+  fn draw(&self, mark: impl Fn(I64Vec2) -> Option<char>) -> String {
+    let mut map = String::new();
+    for row in 0..=self.rows {
+      for col in 0..=self.cols {
+        let pos = I64Vec2::new(row, col);
+        map.push(if self.walls.contains(&pos) {
+          '#'
+        } else if pos == self.start {
+          'S'
+        } else if pos == self.end {
+          'E'
+        } else {
+          mark(pos).unwrap_or('.')
+        });
+      }
+      map.push('\n');
+    }
+    map
+  }
+
+  // A path drawn like the puzzle does, an arrow on each tile for the way the
+  // reindeer went from it
+  pub fn render_path(&self, path: &[MazeTracker]) -> String {
+    let arrows: HashMap<_, _> = path
+      .windows(2)
+      .map(|pair| (pair[0].location, arrow(pair[1].direction)))
+      .collect();
+    self.draw(|pos| arrows.get(&pos).copied())
+  }
+
+  // The best seats from best_seats marked with O
+  pub fn render_seats(&self, seats: &HashSet<I64Vec2>) -> String {
+    self.draw(|pos| seats.contains(&pos).then_some('O'))
+  }
+
+  pub fn start_tracker(&self) -> MazeTracker {
+    MazeTracker {
+      location: self.start,
+      direction: self.heading,
+    }
+  }
+
+  fn is_open(&self, location: I64Vec2) -> bool {
+    !self.walls.contains(&location)
+      && (0..=self.rows).contains(&location.x)
+      && (0..=self.cols).contains(&location.y)
+  }
+
+  // What it costs to turn from one heading to another and step forwards,
+  // or None if that isn't allowed
+  fn move_cost(&self, from: I64Vec2, to: I64Vec2) -> Option<usize> {
+    let diagonal = to.x != 0 && to.y != 0;
+    let step_cost = if diagonal {
+      self.costs.diagonal?
+    } else {
+      self.costs.step
+    };
+    let facing = HEADINGS.iter().position(|&heading| heading == from);
+    let turning = HEADINGS.iter().position(|&heading| heading == to)?;
+    // In eighths of a full turn, whichever way round is shorter
+    let eighths = facing.map_or(0, |facing| {
+      let turned = (turning + 8 - facing) % 8;
+      turned.min(8 - turned)
+    });
+    let turn_cost = match eighths {
+      0 => 0,
+      1 | 2 => self.costs.turn,
+      _ => self.costs.u_turn?,
+    };
+    Some(turn_cost + step_cost)
+  }
+
+  // Every move from `step` and what it costs: turning to a new heading, if
+  // need be, then a step forwards
+  pub fn successors(&self, step: &MazeTracker) -> Vec<(MazeTracker, usize)> {
+    HEADINGS
+      .iter()
+      .filter_map(|&direction| {
+        let location = step.location + direction;
+        let cost = self.move_cost(step.direction, direction)?;
+        self.is_open(location).then_some((
+          MazeTracker {
+            location,
+            direction,
+          },
+          cost,
+        ))
+      })
+      .collect()
+  }
+
+  // Every move that ends at `step`, the other way round from successors
+  pub fn predecessors(&self, step: &MazeTracker) -> Vec<(MazeTracker, usize)> {
+    let location = step.location - step.direction;
+    if !self.is_open(location) {
+      return vec![];
+    }
+    let mut before: Vec<_> = HEADINGS
+      .iter()
+      .map(|&direction| MazeTracker {
+        location,
+        direction,
+      })
+      .collect();
+    // The reindeer might start out facing somewhere odd
+    if location == self.start && !HEADINGS.contains(&self.heading) {
+      before.push(self.start_tracker());
+    }
+    before
+      .into_iter()
+      .filter_map(|previous| {
+        let cost = self.move_cost(previous.direction, step.direction)?;
+        Some((previous, cost))
+      })
+      .collect()
+  }
+
+  // Never more than the real cost of getting to the end
+  fn estimate(&self, step: &MazeTracker) -> usize {
+    let distance = (step.location - self.end).abs();
+    let steps = if self.costs.diagonal.is_some() {
+      distance.max_element()
+    } else {
+      distance.element_sum()
+    };
+    steps as usize * self.costs.cheapest_step()
+  }
+
+  pub fn shortest_path(&self) -> Option<(Vec<MazeTracker>, usize)> {
+    pathfinding::prelude::astar(
+      &self.start_tracker(),
+      |step| self.successors(step),
+      |step| self.estimate(step),
+      |step| step.location == self.end,
+    )
+  }
+
+  // Every cheapest path spelled out, which gets out of hand quickly when
+  // lots of paths cost the same. best_seats gives the same tiles without them
+  pub fn all_shortest_paths(&self) -> Option<(Vec<Vec<MazeTracker>>, usize)> {
+    pathfinding::prelude::astar_bag_collect(
+      &self.start_tracker(),
+      |step| self.successors(step),
+      |step| self.estimate(step),
+      |step| step.location == self.end,
+    )
+  }
+
+  // Up to `k` routes from the start to the end, cheapest first, with what each
+  // costs. Two routes are different if they visit different tiles, so it
+  // finds the runners up as well as any ties for the best
+  pub fn cheapest_routes(&self, k: usize) -> Vec<(Vec<MazeTracker>, usize)> {
+    pathfinding::prelude::yen(
+      &self.start_tracker(),
+      |step| self.successors(step),
+      |step| step.location == self.end,
+      k,
+    )
+  }
+
+  fn end_trackers(&self) -> impl Iterator<Item = MazeTracker> + '_ {
+    HEADINGS.iter().map(|&direction| MazeTracker {
+      location: self.end,
+      direction,
+    })
+  }
+
+  // The cheapest cost to the end, and every tile on any path that costs that.
+  // A tile is on one when the cost to reach it plus the cost from it to the
+  // end is the best there is, so it takes one search from each end
+  pub fn best_seats(&self) -> Option<(usize, HashSet<I64Vec2>)> {
+    let from_start = cheapest(vec![self.start_tracker()], |step| self.successors(step));
+    let best = self
+      .end_trackers()
+      .filter_map(|step| from_start.get(&step))
+      .min()
+      .copied()?;
+    let to_end = cheapest(self.end_trackers().collect(), |step| {
+      self.predecessors(step)
+    });
+    let seats = from_start
+      .iter()
+      .filter(|(step, cost)| to_end.get(step).is_some_and(|rest| *cost + rest == best))
+      .map(|(step, _)| step.location)
+      .collect();
+    Some((best, seats))
+  }
+}
+
+// Dijkstra from every one of `starts` at once, to all the states it can reach
+fn cheapest<F>(starts: Vec<MazeTracker>, neighbours: F) -> HashMap<MazeTracker, usize>
+where
+  F: Fn(&MazeTracker) -> Vec<(MazeTracker, usize)>,
+{
+  let mut costs = HashMap::new();
+  let mut queue: BinaryHeap<_> = starts
+    .into_iter()
+    .map(|step| Reverse((0, step.location.to_array(), step.direction.to_array())))
+    .collect();
+  while let Some(Reverse((cost, location, direction))) = queue.pop() {
+    let step = MazeTracker {
+      location: I64Vec2::from_array(location),
+      direction: I64Vec2::from_array(direction),
+    };
+    if costs.contains_key(&step) {
+      continue;
+    }
+    costs.insert(step, cost);
+    for (next, step_cost) in neighbours(&step) {
+      if !costs.contains_key(&next) {
+        queue.push(Reverse((
+          cost + step_cost,
+          next.location.to_array(),
+          next.direction.to_array(),
+        )));
+      }
+    }
+  }
+  costs
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rstest::rstest;
+
+  // A corridor with the end behind the start
+  const CORRIDOR: &str = "#######
+#E..S.#
+#######";
+
+  #[rstest]
+  #[case(MazeCosts::default(), None)]
+  #[case(MazeCosts { u_turn: Some(2000), ..Default::default() }, Some(2003))]
+  #[case(MazeCosts { step: 5, u_turn: Some(0), ..Default::default() }, Some(15))]
+  fn test_u_turn(#[case] costs: MazeCosts, #[case] expected: Option<usize>) {
+    let maze = Maze::try_from(CORRIDOR).unwrap().with_costs(costs);
+    assert_eq!(expected, maze.shortest_path().map(|(_, cost)| cost));
+  }
+
+  #[test]
+  fn test_heading() {
+    // Drawn facing the end, so there's no turning needed
+    let maze = Maze::try_from(CORRIDOR.replace('S', "<").as_str()).unwrap();
+    assert_eq!(WEST, maze.heading);
+    assert_eq!(Some(3), maze.shortest_path().map(|(_, cost)| cost));
+    let maze = maze.with_heading(NORTH);
+    assert_eq!(
+      Some(1003),
+      maze.shortest_path().map(|(_, cost)| cost),
+      "{maze}"
+    );
+  }
+
+  #[test]
+  fn test_diagonal() {
+    let maze = Maze::try_from(
+      "######
+#...E#
+#.#..#
+#S...#
+######",
+    )
+    .unwrap()
+    .with_heading(NORTH);
+    // Up, then turn east for the rest of the way
+    assert_eq!(Some(1005), maze.shortest_path().map(|(_, cost)| cost));
+    // With cheap turns, going across saves a step
+    let cheap = MazeCosts {
+      turn: 1,
+      ..Default::default()
+    };
+    let maze = maze.with_costs(cheap);
+    assert_eq!(Some(6), maze.shortest_path().map(|(_, cost)| cost));
+    let maze = maze.with_costs(MazeCosts {
+      diagonal: Some(1),
+      ..cheap
+    });
+    // East, then turn north east for two steps
+    let (path, cost) = maze.shortest_path().unwrap();
+    assert_eq!((1 + 1) + (1 + 1) + 1, cost, "{path:?}");
+  }
+
+  #[rstest]
+  #[case(MazeCosts::default())]
+  #[case(MazeCosts { turn: 0, ..Default::default() })]
+  #[case(MazeCosts { u_turn: Some(1), diagonal: Some(3), ..Default::default() })]
+  fn test_best_seats(#[case] costs: MazeCosts) {
+    let maze = Maze::try_from(
+      "#########
+#.......#
+#.#.#.#E#
+#.......#
+#S#.#.#.#
+#.......#
+#########",
+    )
+    .unwrap()
+    .with_costs(costs);
+    let (paths, cost) = maze.all_shortest_paths().unwrap();
+    let tiles: HashSet<_> = paths.iter().flatten().map(|step| step.location).collect();
+    assert_eq!(Some((cost, tiles)), maze.best_seats());
+  }
+
+  #[test]
+  fn test_open_room() {
+    // Without turning costs every way up and right is as good as another,
+    // so every tile is on one of them
+    let room = "#######
+#....E#
+#.....#
+#.....#
+#S....#
+#######";
+    let maze = Maze::try_from(room).unwrap().with_costs(MazeCosts {
+      turn: 0,
+      ..Default::default()
+    });
+    let (cost, seats) = maze.best_seats().unwrap();
+    assert_eq!(7, cost);
+    assert_eq!(20, seats.len());
+    // Shut the end off
+    let closed = room.replace("#....E#\n#.....#", "#...#E#\n#...###");
+    assert_eq!(None, Maze::try_from(closed.as_str()).unwrap().best_seats());
+  }
+
+  #[rstest]
+  #[case::unknown_tile(LOOP.replacen('.', "x", 1))]
+  #[case::no_start(LOOP.replace('S', "."))]
+  #[case::no_end(LOOP.replace('E', "."))]
+  #[case::two_starts(LOOP.replacen('.', "^", 1))]
+  #[case::two_ends(LOOP.replacen('.', "E", 1))]
+  fn test_bad_maze(#[case] input: String) {
+    assert!(Maze::try_from(input.as_str()).is_err());
+  }
+
+  const LOOP: &str = "#######
+#....E#
+#.#.#.#
+#S....#
+#######";
+
+  #[test]
+  fn test_render() {
+    let maze = Maze::try_from(LOOP).unwrap();
+    assert_eq!(format!("{LOOP}\n"), maze.to_string());
+    let (path, _) = maze.shortest_path().unwrap();
+    assert_eq!(
+      "#######
+#....E#
+#.#.#^#
+#S>>>^#
+#######
+",
+      maze.render_path(&path)
+    );
+    let (_, seats) = maze.best_seats().unwrap();
+    assert_eq!(
+      "#######
+#....E#
+#.#.#O#
+#SOOOO#
+#######
+",
+      maze.render_seats(&seats)
+    );
+  }
+
+  #[test]
+  fn test_cheapest_routes() {
+    let maze = Maze::try_from(LOOP).unwrap();
+    let routes = maze.cheapest_routes(3);
+    let costs: Vec<_> = routes.iter().map(|(_, cost)| *cost).collect();
+    // Straight along the bottom, then the two ways with a second turn
+    assert_eq!(vec![1006, 2006, 2006], costs);
+    assert_eq!(maze.shortest_path().unwrap(), routes[0]);
+    assert_eq!(
+      "#######
+#..>>E#
+#.#^#.#
+#S>^..#
+#######
+",
+      maze.render_path(&routes[2].0)
+    );
+    // Asking for more than there are gives them all
+    let all = maze.cheapest_routes(100);
+    assert!(all.len() > 3 && all.len() < 100);
+    assert!(all.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+  }
+}
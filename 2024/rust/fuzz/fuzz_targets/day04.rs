@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Either part may turn the input down, neither may panic
+fuzz_target!(|data: &[u8]| {
+  if let Ok(input) = std::str::from_utf8(data) {
+    let _ = day04::part1::process(input);
+    let _ = day04::part2::process(input);
+  }
+});
@@ -0,0 +1,27 @@
+use generators::inputs::{generate, PUZZLES};
+use miette::{miette, Context, IntoDiagnostic};
+
+// Usage: generate <day> [size] [seed]
+// Prints a random input for the day, e.g. generate 16 141 7 > day16/big.txt
+fn main() -> miette::Result<()> {
+  let mut args = std::env::args().skip(1);
+  let Some(day) = args.next() else {
+    eprintln!("usage: generate <day> [size] [seed]");
+    for (day, puzzle) in PUZZLES.iter().enumerate() {
+      eprintln!("  {:2}  {puzzle}", day + 1);
+    }
+    return Ok(());
+  };
+  let day: u8 = day.parse().into_diagnostic().context("day")?;
+  let size: usize = match args.next() {
+    Some(size) => size.parse().into_diagnostic().context("size")?,
+    None => 50,
+  };
+  let seed: u64 = match args.next() {
+    Some(seed) => seed.parse().into_diagnostic().context("seed")?,
+    None => 0,
+  };
+  let input = generate(day, size, seed).ok_or_else(|| miette!("No generator for day {day}"))?;
+  print!("{input}");
+  Ok(())
+}
@@ -0,0 +1,216 @@
+use std::{
+  fs,
+  panic::{catch_unwind, AssertUnwindSafe},
+  path::{Path, PathBuf},
+};
+
+use crate::{inputs::generate, rng::Rng};
+
+// Sizes and seeds of the generated puzzles every part has to answer
+const SIZES: [usize; 3] = [5, 20, 60];
+const SEEDS: std::ops::Range<u64> = 0..3;
+
+// How many made up inputs each run tries, unless FUZZ_RUNS says otherwise
+const RUNS: usize = 300;
+
+// Things a puzzle input could plausibly contain, and some it really shouldn't
+const TOKENS: [&str; 27] = [
+  "0",
+  "9",
+  "-",
+  "+",
+  "\n",
+  "\r\n",
+  "\n\n",
+  " ",
+  ",",
+  ":",
+  "|",
+  "#",
+  ".",
+  "^",
+  "@",
+  "O",
+  "[]",
+  "S",
+  "E",
+  "=",
+  "é",
+  "99999999999999999999",
+  "9223372036854775807",
+  "-9223372036854775808",
+  "4294967296",
+  "size=0,0\n",
+  "Button A: X+0, Y+0\n",
+];
+
+// One small change: something swapped, added, dropped, repeated or cut off
+fn mutate_once(chars: &mut Vec<char>, rng: &mut Rng) {
+  let at = rng.below(chars.len() + 1);
+  let to = (at + rng.range(1..9) as usize).min(chars.len());
+  match rng.range(0..6) {
+    0 if at < chars.len() => chars[at] = rng.range(0x20..0x7f) as u8 as char,
+    1 => {
+      let token = rng.pick(&TOKENS);
+      chars.splice(at..at, token.chars());
+    }
+    2 => {
+      chars.drain(at..to);
+    }
+    3 => {
+      let repeated: Vec<char> = chars[at..to].to_vec();
+      chars.splice(at..at, repeated);
+    }
+    4 => chars.truncate(at),
+    _ => {
+      // Swap two lines
+      let text: String = chars.iter().collect();
+      let mut lines: Vec<&str> = text.split('\n').collect();
+      let (first, second) = (rng.below(lines.len()), rng.below(lines.len()));
+      lines.swap(first, second);
+      *chars = lines.join("\n").chars().collect();
+    }
+  }
+}
+
+// A few changes to a random input for `day`
+pub fn mutate(day: u8, rng: &mut Rng) -> String {
+  let size = rng.range(1..13) as usize;
+  let seed = rng.next_u64();
+  let mut chars: Vec<char> = generate(day, size, seed)
+    .unwrap_or_default()
+    .chars()
+    .collect();
+  for _ in 0..rng.range(1..5) {
+    mutate_once(&mut chars, rng);
+  }
+  chars.into_iter().collect()
+}
+
+// Made up inputs for `day`. Most are nearly right, some are pure junk
+pub fn inputs(day: u8, seed: u64, count: usize) -> Vec<String> {
+  let mut rng = Rng::new(seed);
+  let mut inputs = vec![String::new(), "\n".to_string()];
+  for _ in 0..count {
+    inputs.push(if rng.chance(10) {
+      (0..rng.range(0..60))
+        .map(|_| rng.pick(b" \n0123456789#.^@O[]SE:,|-+=pvXY") as char)
+        .collect()
+    } else {
+      mutate(day, &mut rng)
+    });
+  }
+  inputs
+}
+
+// Every file directly inside `dir`, in name order
+fn saved(dir: &Path) -> Vec<PathBuf> {
+  let mut saved: Vec<_> = fs::read_dir(dir)
+    .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+    .unwrap_or_default();
+  saved.retain(|path| path.is_file());
+  saved.sort();
+  saved
+}
+
+// Replays the inputs that once broke a part of `day`. Files directly in
+// `corpus` are bad puzzles, so `process` has to turn them down with an Err.
+// Files in `corpus/accepted` are fine puzzles, so it has to give an answer,
+// and so does every puzzle `generate` comes up with. Then a run of made up
+// inputs, none of which may make it panic. Panics itself listing every input
+// that went wrong
+pub fn fuzz<T, E>(day: u8, corpus: impl AsRef<Path>, process: impl Fn(&str) -> Result<T, E>) {
+  let mut failures = vec![];
+  let corpus = corpus.as_ref();
+  let replays = saved(corpus).into_iter().map(|path| (path, false)).chain(
+    saved(&corpus.join("accepted"))
+      .into_iter()
+      .map(|path| (path, true)),
+  );
+  for (path, accepted) in replays {
+    let input = fs::read_to_string(&path).unwrap_or_default();
+    match catch_unwind(AssertUnwindSafe(|| process(&input).is_ok())) {
+      Ok(ok) if ok == accepted => (),
+      Ok(true) => failures.push(format!("{} was accepted", path.display())),
+      Ok(false) => failures.push(format!("{} was turned down", path.display())),
+      Err(_) => failures.push(format!("{} panicked", path.display())),
+    }
+  }
+
+  for size in SIZES {
+    for seed in SEEDS {
+      let input = generate(day, size, seed).unwrap_or_default();
+      match catch_unwind(AssertUnwindSafe(|| process(&input).is_ok())) {
+        Ok(true) => (),
+        Ok(false) => failures.push(format!("generated size {size} seed {seed} was turned down")),
+        Err(_) => failures.push(format!("generated size {size} seed {seed} panicked")),
+      }
+    }
+  }
+
+  let runs = std::env::var("FUZZ_RUNS")
+    .ok()
+    .and_then(|runs| runs.parse().ok())
+    .unwrap_or(RUNS);
+  for input in inputs(day, day.into(), runs) {
+    if catch_unwind(AssertUnwindSafe(|| process(&input))).is_err() {
+      failures.push(format!("panicked on {input:?}"));
+    }
+  }
+  assert!(
+    failures.is_empty(),
+    "day {day}, {} failures:\n{}",
+    failures.len(),
+    failures.join("\n")
+  );
+}
+
+// The fuzz tests for both parts of a day, with the corpus kept in the day's own
+// crate. Goes in the day's lib.rs, next to part1 and part2, behind #[cfg(test)]
+#[macro_export]
+macro_rules! fuzz_tests {
+  ($day:expr) => {
+    mod fuzz {
+      const CORPUS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/corpus");
+
+      #[test]
+      fn test_fuzz_part1() {
+        $crate::fuzz::fuzz($day, CORPUS, super::part1::process);
+      }
+
+      #[test]
+      fn test_fuzz_part2() {
+        $crate::fuzz::fuzz($day, CORPUS, super::part2::process);
+      }
+    }
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_inputs_repeat() {
+    assert_eq!(inputs(5, 1, 50), inputs(5, 1, 50));
+    assert_ne!(inputs(5, 1, 50), inputs(5, 2, 50));
+  }
+
+  #[test]
+  #[should_panic(expected = "generated size 5 seed 0 was turned down")]
+  fn test_fuzz_needs_generated_answers() {
+    fuzz(9, "no corpus here", |_: &str| Err::<(), _>("no"));
+  }
+
+  #[test]
+  #[should_panic(expected = "panicked on")]
+  fn test_fuzz_catches_panics() {
+    fuzz(9, "no corpus here", |input: &str| {
+      input
+        .chars()
+        .map(|chr| chr.to_digit(10).ok_or(()))
+        .collect::<Result<Vec<_>, _>>()
+        .map(|digits| digits[0])
+    });
+  }
+}
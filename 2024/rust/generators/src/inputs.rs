@@ -0,0 +1,593 @@
+use std::collections::HashSet;
+
+use crate::rng::Rng;
+
+// What each day's input holds, by day
+pub const PUZZLES: [&str; 16] = [
+  "location lists",
+  "reports",
+  "corrupted memory",
+  "word search",
+  "page rules",
+  "guard map",
+  "equations",
+  "antenna map",
+  "disk map",
+  "topographic map",
+  "stones",
+  "garden",
+  "claw machines",
+  "robots",
+  "warehouse",
+  "reindeer maze",
+];
+
+// A random input for `day`, or None if there's no such day. What `size`
+// counts depends on the day: lines, records, or how wide the map is
+pub fn generate(day: u8, size: usize, seed: u64) -> Option<String> {
+  let generator = match day {
+    1 => location_lists,
+    2 => reports,
+    3 => corrupted_memory,
+    4 => word_search,
+    5 => page_rules,
+    6 => guard_map,
+    7 => equations,
+    8 => antenna_map,
+    9 => disk_map,
+    10 => topographic_map,
+    11 => stones,
+    12 => garden,
+    13 => claw_machines,
+    14 => robots,
+    15 => warehouse,
+    16 => reindeer_maze,
+    _ => return None,
+  };
+  Some(generator(size, seed))
+}
+
+// Like the real inputs, every line ends in a newline
+fn lines(lines: impl IntoIterator<Item = String>) -> String {
+  lines.into_iter().map(|line| line + "\n").collect()
+}
+
+fn grid(rows: &[Vec<char>]) -> String {
+  lines(rows.iter().map(|row| row.iter().collect()))
+}
+
+const ALPHANUMERIC: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// Day 1: two columns of numbers, the right one repeating some of the left
+pub fn location_lists(size: usize, seed: u64) -> String {
+  let mut rng = Rng::new(seed);
+  let mut left = vec![];
+  lines((0..size).map(|_| {
+    left.push(rng.range(10_000..100_000));
+    let right = if rng.chance(30) {
+      left[rng.below(left.len())]
+    } else {
+      rng.range(10_000..100_000)
+    };
+    format!("{}   {right}", left[left.len() - 1])
+  }))
+}
+
+// Day 2: levels that mostly climb or fall gently, some with a bad level
+pub fn reports(size: usize, seed: u64) -> String {
+  let mut rng = Rng::new(seed);
+  lines((0..size).map(|_| {
+    let length = rng.range(5..9) as usize;
+    let falling = rng.chance(50);
+    let mut level = rng.range(30..70) as i64;
+    let mut levels = vec![];
+    for _ in 0..length {
+      levels.push(level);
+      let step = rng.range(1..4) as i64;
+      level += if falling { -step } else { step };
+    }
+    if rng.chance(40) {
+      let bad = rng.below(length);
+      levels[bad] = rng.range(1..100) as i64;
+    }
+    levels
+      .iter()
+      .map(i64::to_string)
+      .collect::<Vec<_>>()
+      .join(" ")
+  }))
+}
+
+// Day 3: mul, do and don't instructions buried in junk, some of it nearly right
+pub fn corrupted_memory(size: usize, seed: u64) -> String {
+  let mut rng = Rng::new(seed);
+  let junk = b"!@#$%^&*()[]{}<>?,.;:' xmuldont_+-";
+  let mut memory = String::new();
+  for _ in 0..size {
+    let (a, b) = (rng.range(1..1000), rng.range(1..1000));
+    let piece = match rng.range(0..100) {
+      0..40 => format!("mul({a},{b})"),
+      40..50 => "do()".to_string(),
+      50..60 => "don't()".to_string(),
+      60..65 => format!("mul({a},{b}]"),
+      65..70 => format!("mul[{a},{b})"),
+      70..75 => format!("mul({a}, {b})"),
+      75..80 => format!("mul({}{a},{b})", rng.range(1..10)),
+      // Split over a few lines, like the real thing
+      80..82 if !memory.is_empty() && !memory.ends_with('\n') => "\n".to_string(),
+      _ => (0..rng.range(1..8))
+        .map(|_| rng.pick(junk) as char)
+        .collect(),
+    };
+    memory.push_str(&piece);
+  }
+  if !memory.ends_with('\n') {
+    memory.push('\n');
+  }
+  memory
+}
+
+// Day 4: a square of X, M, A and S with XMAS and crossed MASes hidden in it
+pub fn word_search(size: usize, seed: u64) -> String {
+  let mut rng = Rng::new(seed);
+  let mut rows: Vec<Vec<char>> = (0..size)
+    .map(|_| (0..size).map(|_| rng.pick(b"XMAS") as char).collect())
+    .collect();
+  let inside =
+    |row: i64, col: i64| (0..size as i64).contains(&row) && (0..size as i64).contains(&col);
+  for _ in 0..size {
+    let (row, col) = (rng.below(size) as i64, rng.below(size) as i64);
+    if rng.chance(50) {
+      let (down, across) = rng.pick(&[
+        (0, 1),
+        (1, 1),
+        (1, 0),
+        (1, -1),
+        (0, -1),
+        (-1, -1),
+        (-1, 0),
+        (-1, 1),
+      ]);
+      if inside(row + 3 * down, col + 3 * across) {
+        for (step, letter) in "XMAS".chars().enumerate() {
+          let step = step as i64;
+          rows[(row + step * down) as usize][(col + step * across) as usize] = letter;
+        }
+      }
+    } else if inside(row - 1, col - 1) && inside(row + 1, col + 1) {
+      let (first, second) = (rng.pick(b"MS") as char, rng.pick(b"MS") as char);
+      let other = |letter| if letter == 'M' { 'S' } else { 'M' };
+      let (row, col) = (row as usize, col as usize);
+      rows[row][col] = 'A';
+      rows[row - 1][col - 1] = first;
+      rows[row + 1][col + 1] = other(first);
+      rows[row - 1][col + 1] = second;
+      rows[row + 1][col - 1] = other(second);
+    }
+  }
+  grid(&rows)
+}
+
+// Day 5: ordering rules for every pair of pages, then updates of an odd
+// number of pages, about half of them in the right order
+pub fn page_rules(size: usize, seed: u64) -> String {
+  let mut rng = Rng::new(seed);
+  let mut order: Vec<u64> = (10..100).collect();
+  rng.shuffle(&mut order);
+  order.truncate((size + 5).min(order.len()));
+
+  let mut rules = vec![];
+  for (index, before) in order.iter().enumerate() {
+    for after in &order[index + 1..] {
+      rules.push(format!("{before}|{after}"));
+    }
+  }
+  rng.shuffle(&mut rules);
+
+  let longest = order.len().min(23);
+  let updates = (0..size.max(1)).map(|_| {
+    let length = (rng.range(1..longest as u64 / 2 + 1) * 2 + 1) as usize;
+    let mut pages = order.clone();
+    rng.shuffle(&mut pages);
+    pages.truncate(length);
+    if rng.chance(50) {
+      pages.sort_unstable_by_key(|page| order.iter().position(|other| other == page));
+    }
+    pages
+      .iter()
+      .map(u64::to_string)
+      .collect::<Vec<_>>()
+      .join(",")
+  });
+  lines(rules) + "\n" + &lines(updates)
+}
+
+// Whether the guard at `start` walks off the map rather than going round in
+// circles forever
+fn escapes(rows: &[Vec<char>], start: (usize, usize)) -> bool {
+  let directions = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+  let (mut row, mut col) = (start.0 as i64, start.1 as i64);
+  let mut facing = 0;
+  let mut seen = HashSet::new();
+  while seen.insert((row, col, facing)) {
+    let (down, across) = directions[facing];
+    let (next_row, next_col) = (row + down, col + across);
+    let Some(&cell) = rows
+      .get(next_row as usize)
+      .and_then(|cells| cells.get(next_col as usize))
+    else {
+      return true;
+    };
+    if cell == '#' {
+      facing = (facing + 1) % 4;
+    } else {
+      (row, col) = (next_row, next_col);
+    }
+  }
+  false
+}
+
+// Day 6: a map with scattered obstructions and a guard facing up who
+// eventually walks off it
+pub fn guard_map(size: usize, seed: u64) -> String {
+  let mut rng = Rng::new(seed);
+  let size = size.max(1);
+  loop {
+    let mut rows: Vec<Vec<char>> = (0..size)
+      .map(|_| {
+        (0..size)
+          .map(|_| if rng.chance(8) { '#' } else { '.' })
+          .collect()
+      })
+      .collect();
+    let guard = (rng.below(size), rng.below(size));
+    rows[guard.0][guard.1] = '^';
+    if escapes(&rows, guard) {
+      return grid(&rows);
+    }
+  }
+}
+
+// Day 7: totals made by combining the numbers with +, * and ||, with some
+// of the totals knocked off by one
+pub fn equations(size: usize, seed: u64) -> String {
+  let mut rng = Rng::new(seed);
+  lines((0..size).map(|_| {
+    let numbers: Vec<u64> = (0..rng.range(2..9)).map(|_| rng.range(1..100)).collect();
+    let mut total = numbers[0];
+    for &number in &numbers[1..] {
+      total = match rng.range(0..3) {
+        0 => total + number,
+        1 => total * number,
+        _ => total * 10u64.pow(number.ilog10() + 1) + number,
+      };
+    }
+    if rng.chance(30) {
+      total += 1;
+    }
+    let numbers: Vec<_> = numbers.iter().map(u64::to_string).collect();
+    format!("{total}: {}", numbers.join(" "))
+  }))
+}
+
+// Day 8: a few antennas on each of a handful of frequencies
+pub fn antenna_map(size: usize, seed: u64) -> String {
+  let mut rng = Rng::new(seed);
+  let size = size.max(2);
+  let mut rows = vec![vec!['.'; size]; size];
+  for _ in 0..(size / 4).max(1) {
+    let frequency = rng.pick(ALPHANUMERIC) as char;
+    for _ in 0..rng.range(2..5) {
+      let (row, col) = (rng.below(size), rng.below(size));
+      rows[row][col] = frequency;
+    }
+  }
+  grid(&rows)
+}
+
+// Day 9: `size` files, each between one and nine blocks long, with gaps of
+// up to nine blocks between them
+pub fn disk_map(size: usize, seed: u64) -> String {
+  let mut rng = Rng::new(seed);
+  let mut dense = String::new();
+  for file in 0..size {
+    dense.push(char::from(b'0' + rng.range(1..10) as u8));
+    if file + 1 < size {
+      dense.push(char::from(b'0' + rng.range(0..10) as u8));
+    }
+  }
+  dense + "\n"
+}
+
+// Day 10: random heights with some complete trails from 0 to 9 walked into them
+pub fn topographic_map(size: usize, seed: u64) -> String {
+  let mut rng = Rng::new(seed);
+  let size = size.max(2);
+  let mut rows: Vec<Vec<char>> = (0..size)
+    .map(|_| (0..size).map(|_| rng.pick(b"0123456789") as char).collect())
+    .collect();
+  // Trails don't cross, so a later one can't spoil an earlier one
+  let mut taken = HashSet::new();
+  for _ in 0..size / 2 + 1 {
+    let mut trail = vec![(rng.below(size), rng.below(size))];
+    while trail.len() < 10 {
+      let (row, col) = trail[trail.len() - 1];
+      let next: Vec<_> = [(-1, 0), (0, 1), (1, 0), (0, -1)]
+        .iter()
+        .map(|&(down, across)| ((row as i64 + down) as usize, (col as i64 + across) as usize))
+        .filter(|&(row, col)| row < size && col < size)
+        .filter(|cell| !taken.contains(cell) && !trail.contains(cell))
+        .collect();
+      if next.is_empty() {
+        break;
+      }
+      trail.push(rng.pick(&next));
+    }
+    if trail.len() == 10 && !taken.contains(&trail[0]) {
+      for (height, &(row, col)) in trail.iter().enumerate() {
+        rows[row][col] = char::from(b'0' + height as u8);
+      }
+      taken.extend(trail);
+    }
+  }
+  grid(&rows)
+}
+
+// Day 11: a line of engraved numbers
+pub fn stones(size: usize, seed: u64) -> String {
+  let mut rng = Rng::new(seed);
+  let stones: Vec<_> = (0..size.max(1))
+    .map(|_| rng.range(0..100_000).to_string())
+    .collect();
+  stones.join(" ") + "\n"
+}
+
+// Day 12: plots that mostly take after the plot above or to the left, so
+// the plants grow in patches
+pub fn garden(size: usize, seed: u64) -> String {
+  let mut rng = Rng::new(seed);
+  let plants = &b"ABCDEFGHIJKLMNOPQRSTUVWXYZ"[..(4 + size / 5).min(26)];
+  let mut rows: Vec<Vec<char>> = vec![];
+  for row in 0..size {
+    let mut cells = vec![];
+    for col in 0..size {
+      let mut neighbours = vec![];
+      if row > 0 {
+        neighbours.push(rows[row - 1][col]);
+      }
+      if col > 0 {
+        neighbours.push(cells[col - 1]);
+      }
+      cells.push(if !neighbours.is_empty() && rng.chance(75) {
+        rng.pick(&neighbours)
+      } else {
+        rng.pick(plants) as char
+      });
+    }
+    rows.push(cells);
+  }
+  grid(&rows)
+}
+
+// Day 13: machines whose prize can mostly be reached with up to 100 presses
+// of each button
+pub fn claw_machines(size: usize, seed: u64) -> String {
+  let mut rng = Rng::new(seed);
+  let machines: Vec<_> = (0..size.max(1))
+    .map(|_| {
+      let a = (rng.range(10..100), rng.range(10..100));
+      let b = (rng.range(10..100), rng.range(10..100));
+      let prize = if rng.chance(70) {
+        let (presses_a, presses_b) = (rng.range(0..101), rng.range(0..101));
+        (
+          presses_a * a.0 + presses_b * b.0,
+          presses_a * a.1 + presses_b * b.1,
+        )
+      } else {
+        (rng.range(1000..20_000), rng.range(1000..20_000))
+      };
+      format!(
+        "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}\n",
+        a.0, a.1, b.0, b.1, prize.0, prize.1
+      )
+    })
+    .collect();
+  machines.join("\n")
+}
+
+// Day 14: robots in the puzzle's 101 by 103 arena, with its size written out
+pub fn robots(size: usize, seed: u64) -> String {
+  let mut rng = Rng::new(seed);
+  let (width, height) = (101, 103);
+  let robots = (0..size.max(1)).map(|_| {
+    let velocity = |rng: &mut Rng| rng.range(0..201) as i64 - 100;
+    format!(
+      "p={},{} v={},{}",
+      rng.range(0..width),
+      rng.range(0..height),
+      velocity(&mut rng),
+      velocity(&mut rng)
+    )
+  });
+  format!("size={width},{height}\n") + &lines(robots)
+}
+
+// Day 15: a walled warehouse with boxes, a few inner walls and the robot,
+// then its moves
+pub fn warehouse(size: usize, seed: u64) -> String {
+  let mut rng = Rng::new(seed);
+  let size = size.max(3);
+  let mut rows: Vec<Vec<char>> = (0..size)
+    .map(|row| {
+      (0..size)
+        .map(|col| {
+          if row == 0 || col == 0 || row == size - 1 || col == size - 1 || rng.chance(8) {
+            '#'
+          } else if rng.chance(20) {
+            'O'
+          } else {
+            '.'
+          }
+        })
+        .collect()
+    })
+    .collect();
+  rows[rng.range(1..size as u64 - 1) as usize][rng.range(1..size as u64 - 1) as usize] = '@';
+  let moves: Vec<u8> = (0..size * size * 8).map(|_| rng.pick(b"<>^v")).collect();
+  grid(&rows)
+    + "\n"
+    + &lines(
+      moves
+        .chunks(70)
+        .map(|line| String::from_utf8_lossy(line).into_owned()),
+    )
+}
+
+// Day 16: a maze carved out by a random depth first walk, with a few extra
+// walls knocked through so there's more than one way round. S is in the
+// bottom left and E the top right
+pub fn reindeer_maze(size: usize, seed: u64) -> String {
+  let mut rng = Rng::new(seed);
+  // The walk goes between odd cells, so the sides need to be odd
+  let size = size.max(5) | 1;
+  let mut rows = vec![vec!['#'; size]; size];
+  let start = (size - 2, 1);
+  rows[start.0][start.1] = '.';
+  let mut stack = vec![start];
+  while let Some(&(row, col)) = stack.last() {
+    let mut next: Vec<(usize, usize)> = [(0, 2), (2, 0), (0, -2), (-2, 0)]
+      .iter()
+      .map(|&(down, across)| ((row as i64 + down) as usize, (col as i64 + across) as usize))
+      .filter(|&(row, col)| (1..size - 1).contains(&row) && (1..size - 1).contains(&col))
+      .filter(|&(row, col)| rows[row][col] == '#')
+      .collect();
+    if next.is_empty() {
+      stack.pop();
+      continue;
+    }
+    rng.shuffle(&mut next);
+    let (next_row, next_col) = next[0];
+    rows[(row + next_row) / 2][(col + next_col) / 2] = '.';
+    rows[next_row][next_col] = '.';
+    stack.push((next_row, next_col));
+  }
+  // Walls sitting between two open cells, in a straight line
+  for (row, cells) in rows.iter_mut().enumerate().take(size - 1).skip(1) {
+    for (col, cell) in cells.iter_mut().enumerate().take(size - 1).skip(1) {
+      let between = (row % 2 == 1) != (col % 2 == 1);
+      if between && *cell == '#' && rng.chance(10) {
+        *cell = '.';
+      }
+    }
+  }
+  rows[start.0][start.1] = 'S';
+  rows[1][size - 2] = 'E';
+  grid(&rows)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rstest::rstest;
+
+  #[rstest]
+  fn test_every_day(#[values(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16)] day: u8) {
+    let input = generate(day, 20, 7).unwrap();
+    assert_eq!(input, generate(day, 20, 7).unwrap());
+    assert_ne!(input, generate(day, 20, 8).unwrap());
+    assert!(input.ends_with('\n') && !input.ends_with("\n\n"));
+    assert!(input.is_ascii());
+  }
+
+  #[test]
+  fn test_no_such_day() {
+    assert_eq!(None, generate(17, 10, 1));
+    assert_eq!(None, generate(0, 10, 1));
+  }
+
+  #[rstest]
+  #[case(6, guard_map)]
+  #[case(15, warehouse)]
+  #[case(16, reindeer_maze)]
+  fn test_maps_are_rectangular(#[case] size: usize, #[case] generator: fn(usize, u64) -> String) {
+    for seed in 0..20 {
+      let input = generator(size, seed);
+      let map = input.split("\n\n").next().unwrap();
+      assert!(map
+        .lines()
+        .all(|line| line.len() == map.lines().next().unwrap().len()));
+    }
+  }
+
+  #[test]
+  fn test_guard_escapes() {
+    for seed in 0..50 {
+      let rows: Vec<Vec<char>> = guard_map(15, seed)
+        .lines()
+        .map(|line| line.chars().collect())
+        .collect();
+      let row = rows.iter().position(|cells| cells.contains(&'^')).unwrap();
+      let col = rows[row].iter().position(|&cell| cell == '^').unwrap();
+      assert!(escapes(&rows, (row, col)));
+    }
+    // Boxed in, the guard turns round and round
+    let boxed: Vec<Vec<char>> = [".#.", "#^#", ".#."]
+      .iter()
+      .map(|line| line.chars().collect())
+      .collect();
+    assert!(!escapes(&boxed, (1, 1)));
+  }
+
+  #[test]
+  fn test_page_rules() {
+    let input = page_rules(30, 3);
+    let (rules, updates) = input.split_once("\n\n").unwrap();
+    assert_eq!(35 * 34 / 2, rules.lines().count());
+    assert_eq!(30, updates.lines().count());
+    assert!(updates
+      .lines()
+      .all(|update| update.split(',').count() % 2 == 1));
+  }
+
+  #[test]
+  fn test_reindeer_maze() {
+    for seed in 0..20 {
+      let input = reindeer_maze(14, seed);
+      let rows: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+      assert_eq!(15, rows.len());
+      // Every open cell can be reached from S
+      let mut seen = HashSet::from([(13, 1)]);
+      let mut queue = vec![(13, 1)];
+      while let Some((row, col)) = queue.pop() {
+        for (next_row, next_col) in [
+          (row - 1, col),
+          (row + 1, col),
+          (row, col - 1),
+          (row, col + 1),
+        ] {
+          if rows[next_row][next_col] != b'#' && seen.insert((next_row, next_col)) {
+            queue.push((next_row, next_col));
+          }
+        }
+      }
+      let open = input
+        .bytes()
+        .filter(|&cell| matches!(cell, b'.' | b'S' | b'E'))
+        .count();
+      assert_eq!(open, seen.len());
+      assert!(seen.contains(&(1, 13)));
+    }
+  }
+
+  #[test]
+  fn test_warehouse() {
+    let input = warehouse(10, 5);
+    let (map, moves) = input.split_once("\n\n").unwrap();
+    assert_eq!(1, map.matches('@').count());
+    assert_eq!(
+      800,
+      moves.chars().filter(|chr| "<>^v".contains(*chr)).count()
+    );
+    assert!(moves.lines().all(|line| line.len() <= 70));
+  }
+}
@@ -0,0 +1,3 @@
+pub mod fuzz;
+pub mod inputs;
+pub mod rng;
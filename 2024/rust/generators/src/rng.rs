@@ -0,0 +1,64 @@
+use std::ops::Range;
+
+// SplitMix64. Small, fast and the same on every machine, so a seed always
+// gives the same input back
+#[derive(Debug, Clone)]
+pub struct Rng {
+  state: u64,
+}
+
+impl Rng {
+  pub fn new(seed: u64) -> Self {
+    Self { state: seed }
+  }
+
+  pub fn next_u64(&mut self) -> u64 {
+    self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = self.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+  }
+
+  // Somewhere in `range`, which mustn't be empty
+  pub fn range(&mut self, range: Range<u64>) -> u64 {
+    range.start + self.next_u64() % (range.end - range.start)
+  }
+
+  pub fn below(&mut self, n: usize) -> usize {
+    self.range(0..n as u64) as usize
+  }
+
+  // True `percent` times out of a hundred
+  pub fn chance(&mut self, percent: u64) -> bool {
+    self.range(0..100) < percent
+  }
+
+  pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+    items[self.below(items.len())]
+  }
+
+  pub fn shuffle<T>(&mut self, items: &mut [T]) {
+    for i in (1..items.len()).rev() {
+      items.swap(i, self.below(i + 1));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_repeatable() {
+    let first: Vec<_> = (0..10)
+      .map({
+        let mut rng = Rng::new(42);
+        move |_| rng.next_u64()
+      })
+      .collect();
+    let mut rng = Rng::new(42);
+    assert!(first.iter().all(|&n| n == rng.next_u64()));
+    assert!((0..1000).all(|_| (5..9).contains(&rng.range(5..9))));
+  }
+}